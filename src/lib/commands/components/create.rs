@@ -0,0 +1,99 @@
+use super::*;
+use clap::Parser;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct CreateComponentArgs {
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(long, short, help = "name of the component")]
+    pub(crate) name: String,
+    #[arg(long, short, help = COMPONENT_DESCRIPTION_HELP)]
+    pub(crate) description: Option<String>,
+    #[arg(long, short, help = LEAD_HELP)]
+    pub(crate) lead: Option<String>,
+    #[arg(long, value_enum, help = DEFAULT_ASSIGNEE_HELP)]
+    pub(crate) default_assignee: Option<DefaultAssignee>,
+}
+
+fn assemble_create_args(args: CreateComponentArgs) -> HashMap<&'static str, String> {
+    let mut params: HashMap<&str, String> = HashMap::new();
+    params.insert("name", args.name);
+    params.insert("project", args.project);
+    if let Some(description) = args.description {
+        params.insert("description", description);
+    }
+    if let Some(lead) = args.lead {
+        params.insert("leadAccountId", lead);
+    }
+    if let Some(assignee) = args.default_assignee {
+        params.insert("assigneeType", assignee.as_str().to_string());
+    }
+    params
+}
+
+pub fn execute_create_component(ctx: &Cli, args: &CreateComponentArgs) -> Result<(), AppError> {
+    if let Some(component) = do_create_component(ctx, args)? {
+        util::format_print(vec![component], ctx)?;
+    }
+    Ok(())
+}
+
+pub fn do_create_component(
+    ctx: &Cli,
+    args: &CreateComponentArgs,
+) -> Result<Option<Component>, AppError> {
+    let req_url = format!("{}/rest/api/3/component", ctx.base_jira_url);
+    util::do_post::<Component, HashMap<&str, String>>(
+        &req_url,
+        ctx,
+        &(assemble_create_args(args.clone())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn creates_with_lead_and_default_assignee() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/component"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "name": "api",
+                    "project": "FOO",
+                    "leadAccountId": "abc",
+                    "assigneeType": "COMPONENT_LEAD"
+                })))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "id": "7",
+                "name": "api",
+                "lead": {"accountId": "abc", "displayName": "Ann"},
+                "assigneeType": "COMPONENT_LEAD",
+                "project": "FOO"
+            }))),
+        );
+
+        let component = do_create_component(
+            &ctx,
+            &CreateComponentArgs {
+                project: "FOO".to_string(),
+                name: "api".to_string(),
+                description: None,
+                lead: Some("abc".to_string()),
+                default_assignee: Some(DefaultAssignee::ComponentLead),
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(component.id, "7");
+        assert_eq!(component.lead.unwrap().account_id, "abc");
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+use clap::Parser;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct DeleteComponentArgs {
+    #[arg(long, short, help = COMPONENT_HELP)]
+    pub(crate) component: String,
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(long, short, help = BY_ID_HELP)]
+    pub(crate) by_id: Option<bool>,
+    #[arg(
+        long,
+        help = "for tickets with this component, replace it with this other component"
+    )]
+    pub(crate) replace_component: Option<String>,
+}
+
+pub fn execute_delete_component(ctx: &Cli, args: &DeleteComponentArgs) -> Result<(), AppError> {
+    let id = component_id(ctx, &args.project, &args.component, args.by_id)?;
+    let req_url = format!("{}/rest/api/3/component/{}", ctx.base_jira_url, id);
+    let mut query_params: HashMap<&str, String> = HashMap::new();
+    if let Some(replacement) = &args.replace_component {
+        query_params.insert(
+            "moveIssuesTo",
+            component_id(ctx, &args.project, replacement, args.by_id)?,
+        );
+    }
+    util::do_delete(&req_url, ctx, query_params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn moves_issues_to_the_replacement() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/FOO/component",
+            ))
+            .times(2)
+            .respond_with(json_encoded(serde_json::json!({
+                "total": 3,
+                "startAt": 0,
+                "isLast": true,
+                "values": [
+                    {"id": "1", "name": "api"},
+                    {"id": "2", "name": "api-v2"},
+                    {"id": "3", "name": "backend"}
+                ]
+            }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("DELETE", "/rest/api/3/component/1"),
+                request::query(url_decoded(contains(("moveIssuesTo", "3")))),
+            ])
+            .respond_with(status_code(204)),
+        );
+
+        execute_delete_component(
+            &ctx,
+            &DeleteComponentArgs {
+                component: "api".to_string(),
+                project: "FOO".to_string(),
+                by_id: None,
+                replace_component: Some("backend".to_string()),
+            },
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+use super::*;
+use crate::lib::util::{PageBean, PageRequest, Paginator, MAX_ITEMS_HELP};
+use clap::Parser;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ListComponentsArgs {
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(
+        long,
+        short,
+        help = "optionally filter results; substring match in the name and description fields"
+    )]
+    pub(crate) filter: Option<String>,
+    #[arg(
+        long,
+        short,
+        default_value_t = false,
+        help = "automatically query until all pages have been obtained"
+    )]
+    pub(crate) unpaginate: bool,
+    #[arg(long, default_value_t = 50, help = "how many items to return")]
+    pub(crate) page_size: u64,
+    #[arg(long, default_value_t = 0, help = "item index to begin paging at")]
+    pub(crate) page_start_idx: u64,
+    #[arg(long, help = MAX_ITEMS_HELP)]
+    pub(crate) max_items: Option<u64>,
+}
+
+pub fn execute_list_components(ctx: &Cli, args: &ListComponentsArgs) -> Result<(), AppError> {
+    util::format_stream(iter_components(ctx, args), ctx)
+}
+
+pub(crate) fn do_list_components(
+    ctx: &Cli,
+    args: &ListComponentsArgs,
+) -> Result<Vec<Component>, AppError> {
+    iter_components(ctx, args).collect()
+}
+
+/// Yields components as each page arrives.
+pub(crate) fn iter_components<'a>(
+    ctx: &'a Cli,
+    args: &ListComponentsArgs,
+) -> impl Iterator<Item = Result<Component, AppError>> + 'a {
+    let req_url = format!(
+        "{}/rest/api/3/project/{}/component",
+        ctx.base_jira_url, args.project
+    );
+    let filter = args.filter.clone();
+    Paginator::new(
+        args.page_start_idx,
+        args.page_size,
+        args.unpaginate,
+        move |page: &PageRequest| {
+            let mut query_params = HashMap::<&str, String>::new();
+            query_params.insert("startAt", page.start_at.to_string());
+            query_params.insert("maxResults", page.max_results.to_string());
+            if let Some(filter) = &filter {
+                query_params.insert("query", filter.clone());
+            }
+            util::do_get::<PageBean<Component>, HashMap<&str, String>>(&req_url, ctx, query_params)
+        },
+    )
+    .max_items(args.max_items)
+}
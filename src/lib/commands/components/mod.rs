@@ -0,0 +1,83 @@
+use log::debug;
+use serde::Deserialize;
+use serde::Serialize;
+
+pub use create::*;
+pub use delete::*;
+pub use list::*;
+pub use update::*;
+
+use crate::lib::commands::issues::IssueUser;
+use crate::lib::{util, AppError};
+use crate::Cli;
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+mod create;
+mod delete;
+mod list;
+mod update;
+
+pub use crate::lib::commands::releases::{BY_ID_HELP, PROJECT_HELP};
+pub const COMPONENT_HELP: &str = "the name or id of the component to perform the operation upon";
+pub const COMPONENT_DESCRIPTION_HELP: &str = "description of the component";
+pub const LEAD_HELP: &str = "account id of the component lead";
+pub const DEFAULT_ASSIGNEE_HELP: &str = "who new issues with this component are assigned to";
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Component {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) lead: Option<IssueUser>,
+    pub(crate) assignee_type: Option<String>,
+    pub(crate) project: Option<String>,
+    pub(crate) issue_count: Option<u64>,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum DefaultAssignee {
+    ProjectDefault,
+    ComponentLead,
+    ProjectLead,
+    Unassigned,
+}
+
+impl DefaultAssignee {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DefaultAssignee::ProjectDefault => "PROJECT_DEFAULT",
+            DefaultAssignee::ComponentLead => "COMPONENT_LEAD",
+            DefaultAssignee::ProjectLead => "PROJECT_LEAD",
+            DefaultAssignee::Unassigned => "UNASSIGNED",
+        }
+    }
+}
+
+/// The id of `component`, looking it up by exact name unless `by_id` says it already is one.
+pub(crate) fn component_id(
+    ctx: &Cli,
+    project: &str,
+    component: &str,
+    by_id: Option<bool>,
+) -> Result<String, AppError> {
+    if by_id == Some(true) {
+        return Ok(component.to_string());
+    }
+    let args = ListComponentsArgs {
+        project: project.to_string(),
+        filter: Some(component.to_string()),
+        unpaginate: true,
+        page_size: 100,
+        page_start_idx: 0,
+        max_items: None,
+    };
+    let id = do_list_components(ctx, &args)?
+        .into_iter()
+        .find(|c| c.name == component)
+        .map(|c| c.id)
+        .ok_or_else(|| AppError::ComponentNotFound(component.to_string()))?;
+    debug!("found component {} for name {}", id, component);
+    Ok(id)
+}
@@ -0,0 +1,58 @@
+use super::*;
+use clap::Parser;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct UpdateComponentArgs {
+    #[arg(long, short, help = COMPONENT_HELP)]
+    pub(crate) component: String,
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(long, short, help = BY_ID_HELP)]
+    pub(crate) by_id: Option<bool>,
+    #[arg(long, short, help = "new name of the component")]
+    pub(crate) name: Option<String>,
+    #[arg(long, short, help = COMPONENT_DESCRIPTION_HELP)]
+    pub(crate) description: Option<String>,
+    #[arg(long, short, help = LEAD_HELP)]
+    pub(crate) lead: Option<String>,
+    #[arg(long, value_enum, help = DEFAULT_ASSIGNEE_HELP)]
+    pub(crate) default_assignee: Option<DefaultAssignee>,
+}
+
+fn assemble_update_args(args: UpdateComponentArgs) -> HashMap<&'static str, String> {
+    let mut params: HashMap<&str, String> = HashMap::new();
+    if let Some(name) = args.name {
+        params.insert("name", name);
+    }
+    if let Some(description) = args.description {
+        params.insert("description", description);
+    }
+    if let Some(lead) = args.lead {
+        params.insert("leadAccountId", lead);
+    }
+    if let Some(assignee) = args.default_assignee {
+        params.insert("assigneeType", assignee.as_str().to_string());
+    }
+    params
+}
+
+pub fn execute_update_component(ctx: &Cli, args: &UpdateComponentArgs) -> Result<(), AppError> {
+    if let Some(component) = do_update_component(ctx, args)? {
+        util::format_print(vec![component], ctx)?;
+    }
+    Ok(())
+}
+
+pub fn do_update_component(
+    ctx: &Cli,
+    args: &UpdateComponentArgs,
+) -> Result<Option<Component>, AppError> {
+    let id = component_id(ctx, &args.project, &args.component, args.by_id)?;
+    let req_url = format!("{}/rest/api/3/component/{}", ctx.base_jira_url, id);
+    util::do_put::<Component, HashMap<&str, String>>(
+        &req_url,
+        ctx,
+        &(assemble_update_args(args.clone())),
+    )
+}
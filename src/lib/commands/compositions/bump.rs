@@ -0,0 +1,216 @@
+use super::*;
+use crate::lib::commands::issues::Issue;
+use clap::ValueEnum;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// What a rule looks at; written before the colon in `--bump-rule`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BumpRuleKind {
+    /// the issue type name, ex Bug
+    Type,
+    /// one of the issue's labels
+    Label,
+    /// the type of a conventional commit, ex feat
+    Commit,
+}
+
+/// Maps an issue type, label or conventional commit type onto the bump it implies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BumpRule {
+    kind: BumpRuleKind,
+    value: String,
+    bump: Bump,
+}
+
+/// Parses `type:Bug=patch`, `label:breaking-change=major` or `commit:feat=minor`.
+pub fn parse_bump_rule(s: &str) -> Result<BumpRule, String> {
+    let invalid = || {
+        format!(
+            "expected type:<name>=<bump>, label:<name>=<bump> or commit:<type>=<bump>, got '{}'",
+            s
+        )
+    };
+    let (kind, rest) = s.split_once(':').ok_or_else(invalid)?;
+    let (value, bump) = rest.rsplit_once('=').ok_or_else(invalid)?;
+    let kind = match kind {
+        "type" => BumpRuleKind::Type,
+        "label" => BumpRuleKind::Label,
+        "commit" => BumpRuleKind::Commit,
+        _ => return Err(invalid()),
+    };
+    let bump = Bump::from_str(bump, true)?;
+    Ok(BumpRule {
+        kind,
+        value: value.to_string(),
+        bump,
+    })
+}
+
+/// The rules used when none are given for the same type, label or commit type.
+fn default_rules() -> Vec<BumpRule> {
+    [
+        "type:Bug=patch",
+        "type:Story=minor",
+        "type:Feature=minor",
+        "label:breaking-change=major",
+        "commit:fix=patch",
+        "commit:perf=patch",
+        "commit:feat=minor",
+    ]
+    .iter()
+    .map(|r| parse_bump_rule(r).unwrap())
+    .collect()
+}
+
+/// The default rules with `custom` layered on top; a custom rule replaces the default for the
+/// same kind and value.
+pub(crate) fn bump_rules(custom: &[BumpRule]) -> Vec<BumpRule> {
+    let mut rules: Vec<BumpRule> = default_rules()
+        .into_iter()
+        .filter(|d| {
+            !custom
+                .iter()
+                .any(|c| c.kind == d.kind && c.value.eq_ignore_ascii_case(&d.value))
+        })
+        .collect();
+    rules.extend(custom.iter().cloned());
+    rules
+}
+
+/// The largest bump implied by the issues' types and labels and by any conventional commits.
+/// Anything no rule matches counts as a patch.
+pub(crate) fn auto_bump(
+    ctx: &Cli,
+    rules: &[BumpRule],
+    issues: &[String],
+    commits: &[String],
+) -> Result<Bump, AppError> {
+    let mut bump = Bump::Patch;
+    for issue in search_by_keys(ctx, issues, None)? {
+        if let Some(b) = issue_bump(rules, &issue) {
+            debug!("issue {0} implies a {1:?} bump", issue.key, b);
+            bump = bump.max(b);
+        }
+    }
+    for message in commits {
+        if let Some(b) = commit_bump(rules, message) {
+            bump = bump.max(b);
+        }
+    }
+    info!("working out the release name with a {0:?} bump", bump);
+    Ok(bump)
+}
+
+fn issue_bump(rules: &[BumpRule], issue: &Issue) -> Option<Bump> {
+    let issue_type = issue.fields.issuetype.as_ref().map(|t| t.name.as_str());
+    rules
+        .iter()
+        .filter(|r| match r.kind {
+            BumpRuleKind::Type => issue_type.is_some_and(|t| t.eq_ignore_ascii_case(&r.value)),
+            BumpRuleKind::Label => issue.fields.labels.contains(&r.value),
+            BumpRuleKind::Commit => false,
+        })
+        .map(|r| r.bump)
+        .max()
+}
+
+/// The bump for a conventional commit message such as `feat(api)!: drop v1`. A `!` after the type
+/// or a `BREAKING CHANGE:` footer is always major.
+fn commit_bump(rules: &[BumpRule], message: &str) -> Option<Bump> {
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    let header = HEADER
+        .get_or_init(|| Regex::new(r"^(?P<type>[A-Za-z]+)(\([^)]*\))?(?P<breaking>!)?: ").unwrap());
+    let captures = header.captures(message)?;
+    let breaking = captures.name("breaking").is_some()
+        || message
+            .lines()
+            .any(|l| l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:"));
+    if breaking {
+        return Some(Bump::Major);
+    }
+    let commit_type = &captures["type"];
+    rules
+        .iter()
+        .filter(|r| r.kind == BumpRuleKind::Commit && r.value.eq_ignore_ascii_case(commit_type))
+        .map(|r| r.bump)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(
+            parse_bump_rule("type:Spike=minor"),
+            Ok(BumpRule {
+                kind: BumpRuleKind::Type,
+                value: "Spike".to_string(),
+                bump: Bump::Minor
+            })
+        );
+        assert!(parse_bump_rule("Spike=minor").is_err());
+        assert!(parse_bump_rule("type:Spike=huge").is_err());
+    }
+
+    #[test]
+    fn custom_rules_replace_defaults() {
+        let rules = bump_rules(&[parse_bump_rule("type:bug=minor").unwrap()]);
+        let bug_rules: Vec<&BumpRule> = rules
+            .iter()
+            .filter(|r| r.value.eq_ignore_ascii_case("bug"))
+            .collect();
+        assert_eq!(bug_rules.len(), 1);
+        assert_eq!(bug_rules[0].bump, Bump::Minor);
+    }
+
+    #[test]
+    fn conventional_commits() {
+        let rules = bump_rules(&[]);
+        assert_eq!(commit_bump(&rules, "fix: typo"), Some(Bump::Patch));
+        assert_eq!(
+            commit_bump(&rules, "feat(api): FOO-1 paging"),
+            Some(Bump::Minor)
+        );
+        assert_eq!(commit_bump(&rules, "feat!: drop v1"), Some(Bump::Major));
+        assert_eq!(
+            commit_bump(&rules, "fix: FOO-2\n\nBREAKING CHANGE: new config format"),
+            Some(Bump::Major)
+        );
+        assert_eq!(commit_bump(&rules, "chore: tidy"), None);
+        assert_eq!(commit_bump(&rules, "FOO-3 not conventional"), None);
+    }
+
+    #[test]
+    fn largest_bump_wins() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "issues": [
+                        {"id": "1", "key": "FOO-1", "fields": {
+                            "summary": "", "status": {"id": "1", "name": "Done"}, "fixVersions": [],
+                            "issuetype": {"id": "1", "name": "Bug"}, "labels": []
+                        }},
+                        {"id": "2", "key": "FOO-2", "fields": {
+                            "summary": "", "status": {"id": "1", "name": "Done"}, "fixVersions": [],
+                            "issuetype": {"id": "2", "name": "Story"}, "labels": ["frontend"]
+                        }}
+                    ]
+                })),
+            ),
+        );
+
+        let keys = vec!["FOO-1".to_string(), "FOO-2".to_string()];
+        let bump = auto_bump(&ctx, &bump_rules(&[]), &keys, &["fix: FOO-1".to_string()]).unwrap();
+        assert_eq!(bump, Bump::Minor);
+    }
+}
@@ -0,0 +1,249 @@
+use super::*;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct CompareReleasesArgs {
+    #[arg(help = "name of the first release, ex the patch branch version")]
+    pub a: String,
+
+    #[arg(help = "name of the second release, ex the hotfix version")]
+    pub b: String,
+
+    #[arg(long, short, help = "only look at fix versions in this project")]
+    pub project: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "count issues per status in each release instead of listing them"
+    )]
+    pub by_status: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum Presence {
+    OnlyInA,
+    OnlyInB,
+    Both,
+}
+
+/// One issue found in either release.
+#[derive(Serialize, Debug)]
+struct ComparedIssue {
+    key: String,
+    summary: String,
+    status: String,
+    presence: Presence,
+}
+
+/// How many issues in each release have a given status.
+#[derive(Serialize, Debug, PartialEq)]
+struct StatusCount {
+    status: String,
+    a: usize,
+    b: usize,
+    difference: i64,
+}
+
+pub fn execute_compare_releases(ctx: &Cli, args: &CompareReleasesArgs) -> Result<(), AppError> {
+    let (a, b) = (
+        release_issues(ctx, args, &args.a)?,
+        release_issues(ctx, args, &args.b)?,
+    );
+    if args.by_status {
+        util::format_print(count_statuses(&a, &b), ctx)
+    } else {
+        util::format_print(compare(a, b), ctx)
+    }
+}
+
+fn release_issues(
+    ctx: &Cli,
+    args: &CompareReleasesArgs,
+    release: &str,
+) -> Result<Vec<Issue>, AppError> {
+    let version = format!("fixVersion = {}", jql_string(release));
+    let jql = match &args.project {
+        Some(project) => format!(
+            "project = {} AND {} ORDER BY key",
+            jql_string(project),
+            version
+        ),
+        None => format!("{} ORDER BY key", version),
+    };
+    do_search_issues(ctx, &SearchIssuesArgs::all(jql))
+}
+
+/// A's issues in order, each marked as also in B or not, followed by the issues only in B.
+fn compare(a: Vec<Issue>, b: Vec<Issue>) -> Vec<ComparedIssue> {
+    let a_keys: HashSet<String> = a.iter().map(|i| i.key.clone()).collect();
+    let b_keys: HashSet<String> = b.iter().map(|i| i.key.clone()).collect();
+    let only_in_b = b.into_iter().filter(|i| !a_keys.contains(&i.key));
+    a.into_iter()
+        .map(|i| {
+            let presence = if b_keys.contains(&i.key) {
+                Presence::Both
+            } else {
+                Presence::OnlyInA
+            };
+            (i, presence)
+        })
+        .chain(only_in_b.map(|i| (i, Presence::OnlyInB)))
+        .map(|(i, presence)| ComparedIssue {
+            key: i.key,
+            summary: i.fields.summary,
+            status: i.fields.status.name,
+            presence,
+        })
+        .collect()
+}
+
+fn count_statuses(a: &[Issue], b: &[Issue]) -> Vec<StatusCount> {
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for issue in a {
+        counts
+            .entry(issue.fields.status.name.clone())
+            .or_default()
+            .0 += 1;
+    }
+    for issue in b {
+        counts
+            .entry(issue.fields.status.name.clone())
+            .or_default()
+            .1 += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(status, (a, b))| StatusCount {
+            status,
+            a,
+            b,
+            difference: b as i64 - a as i64,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn issues(list: &[(&str, &str)]) -> serde_json::Value {
+        let issues: Vec<serde_json::Value> = list
+            .iter()
+            .map(|(key, status)| {
+                serde_json::json!({
+                    "id": key,
+                    "key": key,
+                    "fields": {"summary": "", "status": {"id": "1", "name": status}, "fixVersions": []}
+                })
+            })
+            .collect();
+        serde_json::json!({"total": list.len(), "startAt": 0, "issues": issues})
+    }
+
+    fn fetch(a: &[(&str, &str)], b: &[(&str, &str)]) -> (Vec<Issue>, Vec<Issue>) {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/search"),
+                request::body(matches(
+                    r#"project = \\"FOO\\" AND fixVersion = \\"1\.0\\""#
+                )),
+            ])
+            .respond_with(json_encoded(issues(a))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/search"),
+                request::body(matches(
+                    r#"project = \\"FOO\\" AND fixVersion = \\"1\.0\.1\\""#
+                )),
+            ])
+            .respond_with(json_encoded(issues(b))),
+        );
+        let args = CompareReleasesArgs {
+            a: "1.0".to_string(),
+            b: "1.0.1".to_string(),
+            project: Some("FOO".to_string()),
+            by_status: false,
+        };
+        (
+            release_issues(&ctx, &args, &args.a).unwrap(),
+            release_issues(&ctx, &args, &args.b).unwrap(),
+        )
+    }
+
+    #[test]
+    fn quotes_release_and_project() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let jql = r#"project = "MY PROJ" AND fixVersion = "say \"hi\" \\" ORDER BY key"#;
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/search"),
+                request::body(json_decoded(
+                    move |body: &serde_json::Value| body["jql"] == jql
+                )),
+            ])
+            .respond_with(json_encoded(issues(&[]))),
+        );
+        let args = CompareReleasesArgs {
+            a: r#"say "hi" \"#.to_string(),
+            b: "1.0".to_string(),
+            project: Some("MY PROJ".to_string()),
+            by_status: false,
+        };
+        assert!(release_issues(&ctx, &args, &args.a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn splits_issues_by_release() {
+        let (a, b) = fetch(
+            &[("FOO-1", "Done"), ("FOO-2", "Done")],
+            &[("FOO-2", "Done"), ("FOO-3", "In Progress")],
+        );
+        let presence: Vec<(String, Presence)> = compare(a, b)
+            .into_iter()
+            .map(|c| (c.key, c.presence))
+            .collect();
+        assert_eq!(
+            presence,
+            vec![
+                ("FOO-1".to_string(), Presence::OnlyInA),
+                ("FOO-2".to_string(), Presence::Both),
+                ("FOO-3".to_string(), Presence::OnlyInB),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_statuses() {
+        let (a, b) = fetch(
+            &[("FOO-1", "Done"), ("FOO-2", "Done")],
+            &[("FOO-2", "Done"), ("FOO-3", "In Progress")],
+        );
+        assert_eq!(
+            count_statuses(&a, &b),
+            vec![
+                StatusCount {
+                    status: "Done".to_string(),
+                    a: 2,
+                    b: 1,
+                    difference: -1
+                },
+                StatusCount {
+                    status: "In Progress".to_string(),
+                    a: 0,
+                    b: 1,
+                    difference: 1
+                },
+            ]
+        );
+    }
+}
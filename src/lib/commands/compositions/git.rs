@@ -0,0 +1,278 @@
+use super::*;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ReleaseFromGitArgs {
+    #[command(flatten)]
+    pub release: ReleaseCompletedIssuesArgs,
+
+    #[arg(long, default_value = ".", help = "path to the local git repository")]
+    pub repo: PathBuf,
+
+    #[arg(
+        long,
+        help = "ref the release starts after, usually the previous release tag"
+    )]
+    pub from: String,
+
+    #[arg(long, default_value = "HEAD", help = "ref the release ends at")]
+    pub to: String,
+
+    #[arg(
+        long,
+        help = "regex matching issue keys in commit messages; defaults to keys of --project ex FOO-123"
+    )]
+    pub key_pattern: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "only release issues also matched by --issue-jql, or by default done issues without a fix version"
+    )]
+    pub intersect_jql: bool,
+}
+
+pub fn execute_release_from_git(ctx: &Cli, args: &ReleaseFromGitArgs) -> Result<(), AppError> {
+    do_release_from_git(ctx, args)
+}
+
+/// Releases the issues referenced by commits between two refs, through the same
+/// create/tag/release steps as `do_release`.
+pub fn do_release_from_git(ctx: &Cli, args: &ReleaseFromGitArgs) -> Result<(), AppError> {
+    let now: DateTime<Utc> = Utc::now();
+    let mut journal = ReleaseJournal::open(&args.release)?;
+
+    // a resumed run that already knows its issues and name has no need of the history
+    let mut commits = vec![];
+    if journal.issues.is_empty() || journal.release_name.is_empty() {
+        commits = read_commits(&args.repo, &args.from, &args.to)?;
+    }
+    if journal.issues.is_empty() {
+        journal.issues = git_issue_keys(ctx, args, &commits)?;
+        if journal.issues.is_empty() {
+            return Err(AppError::NoIssuesFound);
+        }
+        journal.save()?;
+    }
+    settle_release_name(ctx, &args.release, &mut journal, &commits)?;
+
+    run_release(ctx, &args.release, &mut journal, now.date_naive())
+}
+
+fn git_issue_keys(
+    ctx: &Cli,
+    args: &ReleaseFromGitArgs,
+    commits: &[String],
+) -> Result<Vec<String>, AppError> {
+    let pattern = key_pattern(&args.release.project, args.key_pattern.as_deref())?;
+    let keys = extract_issue_keys(commits, &pattern);
+    debug!(
+        "found {0} issue keys in {1} commits between {2} and {3}",
+        keys.len(),
+        commits.len(),
+        args.from,
+        args.to
+    );
+    if args.intersect_jql && !keys.is_empty() {
+        intersect_with_jql(ctx, keys, &release_jql(ctx, &args.release)?)
+    } else {
+        Ok(keys)
+    }
+}
+
+pub(crate) fn key_pattern(project: &str, custom: Option<&str>) -> Result<Regex, AppError> {
+    let pattern = match custom {
+        Some(p) => p.to_string(),
+        None => format!(r"\b{}-[0-9]+\b", regex::escape(project)),
+    };
+    Regex::new(&pattern).map_err(|e| AppError::GitError(e.to_string()))
+}
+
+/// Commit messages in `from..to`, oldest first.
+pub(crate) fn read_commits(repo: &Path, from: &str, to: &str) -> Result<Vec<String>, AppError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["log", "--reverse", "--format=%B%x1e", "--end-of-options"])
+        .arg(format!("{}..{}", from, to))
+        .output()?;
+    if !output.status.success() {
+        return Err(AppError::GitError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\u{1e}')
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+        .collect())
+}
+
+/// Every distinct key matching `pattern`, in order of first mention.
+pub(crate) fn extract_issue_keys(commits: &[String], pattern: &Regex) -> Vec<String> {
+    let mut seen = HashSet::new();
+    commits
+        .iter()
+        .flat_map(|message| pattern.find_iter(message).map(|m| m.as_str().to_string()))
+        .filter(|key| seen.insert(key.clone()))
+        .collect()
+}
+
+fn intersect_with_jql(ctx: &Cli, keys: Vec<String>, jql: &str) -> Result<Vec<String>, AppError> {
+    let matched: HashSet<String> = search_by_keys(ctx, &keys, Some(jql))?
+        .into_iter()
+        .map(|i| i.key)
+        .collect();
+    Ok(keys.into_iter().filter(|k| matched.contains(k)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+    use std::process::Command;
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn reads_keys_between_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "FOO-1 old work"],
+        );
+        git(dir.path(), &["tag", "v1.0"]);
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "FOO-2: fix\n\nalso touches FOO-3",
+            ],
+        );
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "BAR-9 and FOO-2 again",
+            ],
+        );
+        git(dir.path(), &["tag", "v1.1"]);
+
+        let commits = read_commits(dir.path(), "v1.0", "v1.1").unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0], "FOO-2: fix\n\nalso touches FOO-3");
+
+        let keys = extract_issue_keys(&commits, &key_pattern("FOO", None).unwrap());
+        assert_eq!(keys, vec!["FOO-2", "FOO-3"]);
+    }
+
+    #[test]
+    fn unknown_refs_are_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "initial"],
+        );
+        assert!(matches!(
+            read_commits(dir.path(), "nope", "HEAD"),
+            Err(AppError::GitError(_))
+        ));
+    }
+
+    #[test]
+    fn default_pattern_needs_a_word_boundary() {
+        let commits = vec!["XFOO-1 FOO-12 FOO-".to_string()];
+        let keys = extract_issue_keys(&commits, &key_pattern("FOO", None).unwrap());
+        assert_eq!(keys, vec!["FOO-12"]);
+    }
+
+    #[test]
+    fn intersecting_drops_keys_jira_does_not_know() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let search = |keys: &str| {
+            let jql = format!("key in ({}) AND (status = Done)", keys);
+            all_of![
+                request::method_path("POST", "/rest/api/3/search"),
+                request::body(json_decoded(move |body: &serde_json::Value| {
+                    body["jql"] == jql
+                })),
+            ]
+        };
+        // FOO-9 was moved to another project since it was committed
+        server.expect(
+            Expectation::matching(search("FOO-1,FOO-9")).respond_with(
+                status_code(400).body(
+                    serde_json::json!({
+                        "errorMessages": ["An issue with key 'FOO-9' does not exist for field 'key'."],
+                        "errors": {}
+                    })
+                    .to_string(),
+                ),
+            ),
+        );
+        server.expect(
+            Expectation::matching(search("FOO-1")).respond_with(json_encoded(serde_json::json!({
+                "total": 1,
+                "startAt": 0,
+                "issues": [{
+                    "id": "1",
+                    "key": "FOO-1",
+                    "fields": {"summary": "", "status": {"id": "1", "name": "Done"}, "fixVersions": []}
+                }]
+            }))),
+        );
+
+        let keys = vec!["FOO-1".to_string(), "FOO-9".to_string()];
+        assert_eq!(
+            intersect_with_jql(&ctx, keys, "status = Done").unwrap(),
+            vec!["FOO-1"]
+        );
+    }
+
+    #[test]
+    fn intersecting_returns_other_rejections_at_once() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                status_code(400).body(
+                    serde_json::json!({
+                        "errorMessages": ["Error in the JQL Query: Expecting a field name but got 'AND'."],
+                        "errors": {}
+                    })
+                    .to_string(),
+                ),
+            ),
+        );
+
+        let keys = vec!["FOO-1".to_string(), "FOO-9".to_string()];
+        assert!(matches!(
+            intersect_with_jql(&ctx, keys, "status = Done AND"),
+            Err(AppError::ApiCallRejected(_))
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+use super::ReleaseCompletedIssuesArgs;
+use crate::lib::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Progress of a release run, written after every step so an interrupted run can be resumed.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseJournal {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    pub(crate) project: String,
+    pub(crate) release_name: String,
+    /// The release's id in each project it was created in or found in, by project key.
+    #[serde(default)]
+    pub(crate) version_ids: BTreeMap<String, String>,
+    /// The projects whose release this run created rather than found; only those are deleted by
+    /// a rollback.
+    #[serde(default)]
+    pub(crate) created: BTreeSet<String>,
+    pub(crate) issues: Vec<String>,
+    pub(crate) tagged: Vec<String>,
+    pub(crate) released: bool,
+}
+
+impl ReleaseJournal {
+    /// A fresh journal for these args; only written to disk if `path` is given. The release name
+    /// stays empty until worked out when the args bump a version instead of naming one.
+    pub fn new(args: &ReleaseCompletedIssuesArgs, path: Option<PathBuf>) -> ReleaseJournal {
+        ReleaseJournal {
+            path,
+            project: args.project.clone(),
+            release_name: args.release_name.clone().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// The journal named by `--resume`, or a fresh one written to `--journal` if given.
+    pub fn open(args: &ReleaseCompletedIssuesArgs) -> Result<ReleaseJournal, AppError> {
+        match &args.resume {
+            Some(path) => ReleaseJournal::load(path, args),
+            None => Ok(ReleaseJournal::new(args, args.journal.clone())),
+        }
+    }
+
+    /// Loads a journal written by an earlier run of the same release.
+    pub fn load(path: &Path, args: &ReleaseCompletedIssuesArgs) -> Result<ReleaseJournal, AppError> {
+        let contents = fs::read_to_string(path)?;
+        let mut journal: ReleaseJournal = serde_json::from_str(&contents)
+            .map_err(|e| AppError::InvalidJournal(format!("{}: {}", path.display(), e)))?;
+        let renamed = args
+            .release_name
+            .as_ref()
+            .is_some_and(|name| *name != journal.release_name);
+        if journal.project != args.project || renamed {
+            return Err(AppError::InvalidJournal(format!(
+                "{} records release {} in project {}",
+                path.display(),
+                journal.release_name,
+                journal.project
+            )));
+        }
+        journal.path = Some(path.to_path_buf());
+        Ok(journal)
+    }
+
+    pub fn save(&self) -> Result<(), AppError> {
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string_pretty(self)
+                .map_err(|e| AppError::SerializationError(e.to_string()))?;
+            // write then rename so a crash mid-write never leaves a truncated journal
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, contents)?;
+            fs::rename(&tmp, path)?;
+        }
+        Ok(())
+    }
+
+    pub fn pending(&self) -> Vec<String> {
+        self.issues
+            .iter()
+            .filter(|i| !self.tagged.contains(i))
+            .cloned()
+            .collect()
+    }
+}
@@ -1,25 +1,53 @@
+mod bump;
+mod compare;
+mod git;
+mod journal;
+mod plan;
+mod status;
+
+pub use bump::*;
+pub use compare::*;
+pub use git::*;
+pub use journal::*;
+pub use plan::*;
+pub use status::*;
+
 use super::*;
 use crate::lib::commands::issues::{
-    do_search_issues, execute_update_issue, SearchIssuesArgs, UpdateIssueArgs,
+    do_remove_fix_version, do_search_issues, execute_update_issue, Issue, SearchIssuesArgs,
+    UpdateIssueArgs,
 };
 use crate::lib::commands::releases::{
-    do_update_release, CreateReleaseArgs, UpdateReleaseArgs,
+    do_update_release, execute_delete_release, Bump, CreateReleaseArgs, DeleteReleaseArgs,
+    ReleaseNameArgs, UpdateReleaseArgs,
 };
+use crate::lib::commands::users;
+use crate::lib::util;
 use crate::lib::AppError;
 use crate::lib::AppError::CouldNotCreateRelease;
 use crate::Cli;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::Parser;
-use log::warn;
+use log::{debug, error, info, warn};
 use releases::DESCRIPTION_HELP;
 use releases::NAME_HELP;
 use releases::PROJECT_HELP;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// How many keys go into a single `key in (...)` clause.
+const KEYS_PER_QUERY: usize = 100;
 
 #[derive(Parser, Clone)]
 #[command()]
 pub struct ReleaseCompletedIssuesArgs {
-    #[arg(long, short, help = NAME_HELP)]
-    pub release_name: String,
+    #[arg(
+        long,
+        short,
+        required_unless_present_any = ["bump", "auto_bump", "resume"],
+        help = NAME_HELP
+    )]
+    pub release_name: Option<String>,
 
     #[arg(long, short, help = PROJECT_HELP)]
     pub project: String,
@@ -27,8 +55,72 @@ pub struct ReleaseCompletedIssuesArgs {
     #[arg(long, short, help = "JQL query to determine which issues to release")]
     pub issue_jql: Option<String>,
 
+    #[arg(long, short, help = "only release issues with this component")]
+    pub component: Option<String>,
+
+    #[arg(
+        long,
+        help = "only release issues assigned to this user: an email address, display name, account id, `me` or `unassigned`"
+    )]
+    pub assignee: Option<String>,
+
     #[arg(long, short, help = DESCRIPTION_HELP)]
     pub description: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "all or nothing; if any issue cannot be tagged, undo the tagging, delete the new release and exit non-zero"
+    )]
+    pub atomic: bool,
+
+    #[arg(
+        long,
+        help = "record each step in this file so an interrupted run can be resumed"
+    )]
+    pub journal: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "journal",
+        help = "continue the run recorded in this journal file"
+    )]
+    pub resume: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "release_name",
+        help = "name the release by incrementing the highest existing version instead of giving --release-name"
+    )]
+    pub bump: Option<Bump>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["release_name", "bump"],
+        help = "like --bump, but work out major/minor/patch from the types and labels of the released issues"
+    )]
+    pub auto_bump: bool,
+
+    #[arg(
+        long = "bump-rule",
+        value_parser = parse_bump_rule,
+        help = "extra --auto-bump rule, replacing any default for the same name ex type:Spike=minor, label:breaking-change=major, commit:feat=minor"
+    )]
+    pub bump_rules: Vec<BumpRule>,
+
+    #[command(flatten)]
+    pub naming: ReleaseNameArgs,
+}
+
+/// One line of the report printed when an atomic release is rolled back.
+#[derive(Serialize, Debug)]
+struct ReleaseReportEntry {
+    issue: String,
+    tagged: bool,
+    rolled_back: bool,
+    error: Option<String>,
 }
 
 pub fn execute_do_release(ctx: &Cli, args: &ReleaseCompletedIssuesArgs) -> Result<(), AppError> {
@@ -37,7 +129,71 @@ pub fn execute_do_release(ctx: &Cli, args: &ReleaseCompletedIssuesArgs) -> Resul
 
 pub fn do_release(ctx: &Cli, args: &ReleaseCompletedIssuesArgs) -> Result<(), AppError> {
     let now: DateTime<Utc> = Utc::now();
-    let jql = match args.issue_jql.clone() {
+    let mut journal = ReleaseJournal::open(args)?;
+
+    if journal.issues.is_empty() {
+        journal.issues = find_issue_keys(ctx, release_jql(ctx, args)?)?;
+        if journal.issues.is_empty() {
+            return Err(AppError::NoIssuesFound);
+        }
+        journal.save()?;
+    }
+    settle_release_name(ctx, args, &mut journal, &[])?;
+
+    run_release(ctx, args, &mut journal, now.date_naive())
+}
+
+/// The name given with --release-name, or the next version when bumping.
+pub(crate) fn release_name(
+    ctx: &Cli,
+    args: &ReleaseCompletedIssuesArgs,
+    issues: &[String],
+    commits: &[String],
+) -> Result<String, AppError> {
+    match &args.release_name {
+        Some(name) => Ok(name.clone()),
+        None => {
+            let bump = release_bump(ctx, args, issues, commits)?;
+            releases::do_release_name_to_make(ctx, &args.project, bump, &args.naming)
+        }
+    }
+}
+
+/// The --bump given, or for --auto-bump the one worked out from `issues` and, when they came
+/// from git history, the `commits` naming them.
+pub(crate) fn release_bump(
+    ctx: &Cli,
+    args: &ReleaseCompletedIssuesArgs,
+    issues: &[String],
+    commits: &[String],
+) -> Result<Bump, AppError> {
+    match args.bump {
+        Some(bump) => Ok(bump),
+        None if args.auto_bump => auto_bump(ctx, &bump_rules(&args.bump_rules), issues, commits),
+        None => Err(AppError::InvalidReleaseName(
+            "give one of --release-name, --bump or --auto-bump".to_string(),
+        )),
+    }
+}
+
+/// Records the release name in the journal the first time it is needed, so a resumed run keeps
+/// the name it started with rather than bumping again.
+pub(crate) fn settle_release_name(
+    ctx: &Cli,
+    args: &ReleaseCompletedIssuesArgs,
+    journal: &mut ReleaseJournal,
+    commits: &[String],
+) -> Result<(), AppError> {
+    if journal.release_name.is_empty() {
+        journal.release_name = release_name(ctx, args, &journal.issues, commits)?;
+        journal.save()?;
+    }
+    Ok(())
+}
+
+/// The JQL selecting the issues to release; by default done issues without a fix version.
+pub(crate) fn release_jql(ctx: &Cli, args: &ReleaseCompletedIssuesArgs) -> Result<String, AppError> {
+    let mut jql = match args.issue_jql.clone() {
         None => {
             format!(
                 "(project = {0} AND status = Done) and (fixVersion is EMPTY)",
@@ -46,47 +202,131 @@ pub fn do_release(ctx: &Cli, args: &ReleaseCompletedIssuesArgs) -> Result<(), Ap
         }
         Some(s) => s,
     };
+    if let Some(component) = &args.component {
+        jql = format!("({}) AND component = {}", jql, jql_string(component));
+    }
+    if let Some(assignee) = &args.assignee {
+        jql = format!("({}) AND {}", jql, users::assignee_jql(ctx, assignee)?);
+    }
+    Ok(jql)
+}
+
+/// `value` as a quoted jql string, escaping backslashes and quotes within it.
+pub(crate) fn jql_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
 
-    let issuelist = do_search_issues(
-        ctx,
-        &SearchIssuesArgs {
-            jql,
-            unpaginate: true,
-            page_size: 50,
-            page_start_idx: 0,
-        },
-    )?;
-    if issuelist.is_empty() {
-        return Err(AppError::NoIssuesFound);
-    }
-    let releaseresult = releases::do_create_release(
-        ctx,
-        &CreateReleaseArgs {
-            project: args.project.clone(),
-            name: args.release_name.clone(),
-            description: args.description.clone(),
-            start_date: None,
-            release_date: Some(now.format("%Y-%m-%d").to_string()),
-        },
-    )?;
-    match releaseresult {
-        Some(release) => {
-            issuelist.iter().for_each(|issue| {
-                match execute_update_issue(
+pub(crate) fn find_issue_keys(ctx: &Cli, jql: String) -> Result<Vec<String>, AppError> {
+    Ok(do_search_issues(ctx, &SearchIssuesArgs::all(jql))?
+        .into_iter()
+        .map(|i| i.key)
+        .collect())
+}
+
+/// Looks up the given issues, in batches so the jql stays a manageable length, optionally
+/// narrowed by `jql`. Keys jira does not know, such as those of deleted or moved issues, are
+/// dropped with a warning.
+pub(crate) fn search_by_keys(
+    ctx: &Cli,
+    keys: &[String],
+    jql: Option<&str>,
+) -> Result<Vec<Issue>, AppError> {
+    let mut issues = vec![];
+    let mut unknown = vec![];
+    for chunk in keys.chunks(KEYS_PER_QUERY) {
+        issues.extend(search_key_chunk(ctx, chunk.to_vec(), jql, &mut unknown)?);
+    }
+    if !unknown.is_empty() {
+        warn!(
+            "jira does not know issue(s) {0}, leaving them out",
+            unknown.join(", ")
+        );
+        if unknown.len() == keys.len() {
+            return Err(AppError::NoIssuesFound);
+        }
+    }
+    Ok(issues)
+}
+
+/// Jira rejects a whole `key in (...)` query if any one key does not exist, naming each such key
+/// in its error messages. Those keys are added to `unknown` and the query is run again without
+/// them; any other rejection is returned as it is.
+fn search_key_chunk(
+    ctx: &Cli,
+    mut keys: Vec<String>,
+    jql: Option<&str>,
+    unknown: &mut Vec<String>,
+) -> Result<Vec<Issue>, AppError> {
+    while !keys.is_empty() {
+        let query = match jql {
+            Some(jql) => format!("key in ({}) AND ({})", keys.join(","), jql),
+            None => format!("key in ({})", keys.join(",")),
+        };
+        let messages = match do_search_issues(ctx, &SearchIssuesArgs::all(query)) {
+            Err(AppError::ApiCallRejected(messages)) => messages,
+            found => return found,
+        };
+        let names = |message: &String, key: &String| message.contains(&format!("'{}'", key));
+        if !messages.iter().all(|m| keys.iter().any(|k| names(m, k))) {
+            return Err(AppError::ApiCallRejected(messages));
+        }
+        let (missing, known): (Vec<String>, Vec<String>) = keys
+            .into_iter()
+            .partition(|k| messages.iter().any(|m| names(m, k)));
+        unknown.extend(missing);
+        keys = known;
+    }
+    Ok(vec![])
+}
+
+/// Creates (or picks up) the release in every project the journal's issues belong to, tags the
+/// issues and marks the releases released, skipping any step the journal says is already done.
+pub(crate) fn run_release(
+    ctx: &Cli,
+    args: &ReleaseCompletedIssuesArgs,
+    journal: &mut ReleaseJournal,
+    release_date: NaiveDate,
+) -> Result<(), AppError> {
+    for project in release_projects(&args.project, &journal.issues) {
+        if journal.version_ids.contains_key(&project) {
+            continue;
+        }
+        let existing =
+            releases::find_release_by_name(ctx, project.clone(), journal.release_name.clone())?;
+        let release = match existing {
+            Some(release) => {
+                info!(
+                    "release {0} already exists in {1} as {2}, continuing with it",
+                    journal.release_name, project, release.id
+                );
+                release
+            }
+            None => {
+                let release = releases::do_create_release(
                     ctx,
-                    &UpdateIssueArgs {
-                        name: issue.key.clone(),
-                        fix_version: Some(release.id.clone()),
-                        related_version: None,
-                        use_version_id: true,
+                    &CreateReleaseArgs {
+                        project: project.clone(),
+                        name: journal.release_name.clone(),
+                        description: args.description.clone(),
+                        start_date: None,
+                        release_date: Some(release_date),
                     },
-                ) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        warn!("could not add fix version to issue {0}", issue.key.clone())
-                    }
-                }
-            });
+                )?
+                .ok_or(CouldNotCreateRelease)?;
+                journal.created.insert(project.clone());
+                release
+            }
+        };
+        journal.version_ids.insert(project, release.id);
+        journal.save()?;
+    }
+
+    let failures = tag_issues(ctx, journal)?;
+    if args.atomic && !failures.is_empty() {
+        return roll_back(ctx, journal, failures);
+    }
+    if !journal.released {
+        for (project, release_id) in &journal.version_ids {
             do_update_release(
                 ctx,
                 &UpdateReleaseArgs {
@@ -95,13 +335,700 @@ pub fn do_release(ctx: &Cli, args: &ReleaseCompletedIssuesArgs) -> Result<(), Ap
                     start_date: None,
                     release_date: None,
                     is_released: Some(true),
+                    archived: None,
                     by_id: Some(true),
-                    release: release.id.clone(),
-                    project: args.project.clone(),
+                    release: release_id.clone(),
+                    project: project.clone(),
                 },
             )?;
-            Ok(())
         }
-        _ => Err(CouldNotCreateRelease),
+        journal.released = true;
+        journal.save()?;
+    }
+    Ok(())
+}
+
+/// The project an issue belongs to, from its key.
+pub(crate) fn issue_project(key: &str) -> &str {
+    key.rsplit_once('-').map_or(key, |(project, _)| project)
+}
+
+/// `--project` followed by any other project the issues belong to; a custom jql can match
+/// issues anywhere, and each needs a release of its own project.
+pub(crate) fn release_projects(project: &str, issues: &[String]) -> Vec<String> {
+    let mut projects = vec![project.to_string()];
+    for key in issues {
+        let project = issue_project(key);
+        if !projects.iter().any(|p| p == project) {
+            projects.push(project.to_string());
+        }
+    }
+    projects
+}
+
+/// Adds its own project's release as a fix version on every issue not yet tagged, recording each
+/// one in the journal. Returns the issues that failed.
+fn tag_issues(
+    ctx: &Cli,
+    journal: &mut ReleaseJournal,
+) -> Result<Vec<(String, AppError)>, AppError> {
+    let mut failures = vec![];
+    for key in journal.pending() {
+        let release_id = journal
+            .version_ids
+            .get(issue_project(&key))
+            .cloned()
+            .unwrap_or_default();
+        match execute_update_issue(
+            ctx,
+            &UpdateIssueArgs {
+                name: key.clone(),
+                fix_version: Some(release_id),
+                related_version: None,
+                use_version_id: true,
+                set: vec![],
+                assignee: None,
+            },
+        ) {
+            Ok(_) => {
+                journal.tagged.push(key);
+                journal.save()?;
+            }
+            Err(e) => {
+                warn!("could not add fix version to issue {0}", key);
+                failures.push((key, e));
+            }
+        }
+    }
+    Ok(failures)
+}
+
+/// Undoes a partially tagged release: removes the fix version from every issue that did take it,
+/// then deletes the release from every project this run created it in, leaving any it picked up.
+/// Prints what happened to each issue and always returns an error, a distinct one if any release
+/// could not be deleted.
+fn roll_back(
+    ctx: &Cli,
+    journal: &mut ReleaseJournal,
+    failures: Vec<(String, AppError)>,
+) -> Result<(), AppError> {
+    let failed_count = failures.len();
+    let mut report: Vec<ReleaseReportEntry> = vec![];
+    for key in journal.issues.clone() {
+        match failures.iter().find(|(k, _)| *k == key) {
+            Some((_, e)) => report.push(ReleaseReportEntry {
+                issue: key,
+                tagged: false,
+                rolled_back: false,
+                error: Some(e.to_string()),
+            }),
+            None => {
+                let release_id = journal
+                    .version_ids
+                    .get(issue_project(&key))
+                    .cloned()
+                    .unwrap_or_default();
+                let undone = do_remove_fix_version(ctx, &key, &release_id);
+                if let Err(e) = &undone {
+                    error!("could not remove fix version from issue {0}", key);
+                    report.push(ReleaseReportEntry {
+                        issue: key,
+                        tagged: true,
+                        rolled_back: false,
+                        error: Some(e.to_string()),
+                    });
+                } else {
+                    journal.tagged.retain(|k| *k != key);
+                    report.push(ReleaseReportEntry {
+                        issue: key,
+                        tagged: true,
+                        rolled_back: true,
+                        error: None,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut undeleted = vec![];
+    for (project, release_id) in journal.version_ids.clone() {
+        if !journal.created.contains(&project) {
+            info!(
+                "leaving release {0} in {1}, it existed before this run",
+                release_id, project
+            );
+            journal.version_ids.remove(&project);
+            continue;
+        }
+        match execute_delete_release(
+            ctx,
+            &DeleteReleaseArgs {
+                release: release_id.clone(),
+                project: project.clone(),
+                by_id: Some(true),
+                replace_fix_version: None,
+                replace_affected_version: None,
+            },
+        ) {
+            Ok(_) => {
+                journal.version_ids.remove(&project);
+                journal.created.remove(&project);
+            }
+            Err(e) => {
+                error!(
+                    "could not delete release {0} while rolling back: {1}",
+                    release_id, e
+                );
+                undeleted.push(format!("{} in {}", release_id, project));
+            }
+        }
+    }
+    journal.save()?;
+    util::format_print(report, ctx)?;
+    if undeleted.is_empty() {
+        Err(AppError::ReleaseRolledBack(failed_count))
+    } else {
+        Err(AppError::ReleaseRollbackIncomplete(
+            failed_count,
+            undeleted.join(", "),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn args() -> ReleaseCompletedIssuesArgs {
+        ReleaseCompletedIssuesArgs {
+            release_name: Some("1.0".to_string()),
+            project: "FOO".to_string(),
+            issue_jql: None,
+            component: None,
+            assignee: None,
+            description: None,
+            atomic: false,
+            journal: None,
+            resume: None,
+            bump: None,
+            auto_bump: false,
+            bump_rules: vec![],
+            naming: ReleaseNameArgs::default(),
+        }
+    }
+
+    fn issue(key: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": key,
+            "key": key,
+            "fields": {
+                "summary": "",
+                "status": {"id": "1", "name": "Done"},
+                "fixVersions": []
+            }
+        })
+    }
+
+    #[test]
+    fn atomic_release_rolls_back_on_failure() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            atomic: true,
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "issues": [issue("FOO-1"), issue("FOO-2")]
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 0,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": []
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "id": "10",
+                    "name": "1.0",
+                    "archived": false,
+                    "released": false,
+                    "projectId": 1
+                }))),
+        );
+        // tagging FOO-1 works, then its rollback removes the version again
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"add": {"id": "10"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"remove": {"id": "10"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-2"))
+                .respond_with(status_code(400)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "POST",
+                "/rest/api/3/version/10/removeAndSwap",
+            ))
+            .respond_with(status_code(204)),
+        );
+
+        let res = do_release(&ctx, &args);
+        assert!(matches!(res, Err(AppError::ReleaseRolledBack(1))));
+    }
+
+    #[test]
+    fn rollback_keeps_a_release_it_did_not_create() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            atomic: true,
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "issues": [issue("FOO-1"), issue("FOO-2")]
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "10", "name": "1.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+        // only the fix version this run added is taken off again; the release itself stays
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"add": {"id": "10"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"remove": {"id": "10"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-2"))
+                .respond_with(status_code(400)),
+        );
+        server.expect(
+            Expectation::matching(request::path(matches("removeAndSwap$")))
+                .times(0)
+                .respond_with(status_code(204)),
+        );
+
+        let res = do_release(&ctx, &args);
+        assert!(matches!(res, Err(AppError::ReleaseRolledBack(1))));
+    }
+
+    #[test]
+    fn rollback_reports_a_release_it_could_not_delete() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            atomic: true,
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "issues": [issue("FOO-1")]
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 0,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": []
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "id": "10",
+                    "name": "1.0",
+                    "archived": false,
+                    "released": false,
+                    "projectId": 1
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-1"))
+                .respond_with(status_code(400)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "POST",
+                "/rest/api/3/version/10/removeAndSwap",
+            ))
+            .respond_with(status_code(500)),
+        );
+
+        let res = do_release(&ctx, &args);
+        assert!(
+            matches!(res, Err(AppError::ReleaseRollbackIncomplete(1, ref releases)) if releases == "10 in FOO")
+        );
+    }
+
+    #[test]
+    fn resume_continues_from_journal() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resume.json");
+        std::fs::write(
+            &path,
+            serde_json::json!({
+                "project": "FOO",
+                "releaseName": "1.0",
+                "versionIds": {"FOO": "10"},
+                "issues": ["FOO-1", "FOO-2"],
+                "tagged": ["FOO-1"],
+                "released": false
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let args = ReleaseCompletedIssuesArgs {
+            resume: Some(path.clone()),
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-2"))
+                .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/version/10"),
+                request::body(json_decoded(eq(serde_json::json!({"released": "true"})))),
+            ])
+            .respond_with(status_code(200)),
+        );
+
+        do_release(&ctx, &args).unwrap();
+        let journal = ReleaseJournal::load(&path, &args).unwrap();
+        assert_eq!(journal.tagged, vec!["FOO-1", "FOO-2"]);
+        assert!(journal.released);
+    }
+
+    #[test]
+    fn rerun_picks_up_existing_release() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = args();
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "issues": [issue("FOO-3")]
+                })),
+            ),
+        );
+        // the substring query also matches 1.0.1, which must not be picked up
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "11", "name": "1.0.1", "archived": false, "released": false, "projectId": 1},
+                        {"id": "10", "name": "1.0", "archived": false, "released": true, "projectId": 1}
+                    ]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-3"))
+                .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/version/10"))
+                .respond_with(status_code(200)),
+        );
+
+        do_release(&ctx, &args).unwrap();
+    }
+
+    #[test]
+    fn bumped_rerun_picks_up_the_release_it_created() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            release_name: None,
+            bump: Some(Bump::Minor),
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "issues": [issue("FOO-3")]
+                })),
+            ),
+        );
+        // the first run created 1.3.0 and stopped before releasing it
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .times(2)
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "12", "name": "1.2.0", "archived": false, "released": true, "projectId": 1},
+                        {"id": "13", "name": "1.3.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-3"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"add": {"id": "13"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/version/13"))
+                .respond_with(status_code(200)),
+        );
+
+        do_release(&ctx, &args).unwrap();
+    }
+
+    #[test]
+    fn custom_jql_releases_in_every_issue_project() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            issue_jql: Some("project in (FOO, BAR) AND status = Done".to_string()),
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "issues": [issue("FOO-1"), issue("BAR-2")]
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "10", "name": "1.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/BAR/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 0,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": []
+                }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/version"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "project": "BAR",
+                    "name": "1.0",
+                    "releaseDate": Utc::now().date_naive().to_string()
+                })))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "id": "20",
+                "name": "1.0",
+                "archived": false,
+                "released": false,
+                "projectId": 2
+            }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"add": {"id": "10"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/BAR-2"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"add": {"id": "20"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        for id in ["10", "20"] {
+            server.expect(
+                Expectation::matching(all_of![
+                    request::method_path("PUT", format!("/rest/api/3/version/{}", id)),
+                    request::body(json_decoded(eq(serde_json::json!({"released": "true"})))),
+                ])
+                .respond_with(status_code(200)),
+            );
+        }
+
+        do_release(&ctx, &args).unwrap();
+    }
+
+    #[test]
+    fn rollback_deletes_only_the_releases_this_run_created() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            issue_jql: Some("project in (FOO, BAR) AND status = Done".to_string()),
+            atomic: true,
+            ..args()
+        };
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "issues": [issue("FOO-1"), issue("BAR-2")]
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "10", "name": "1.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/BAR/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 0,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": []
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "id": "20",
+                    "name": "1.0",
+                    "archived": false,
+                    "released": false,
+                    "projectId": 2
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-1"))
+                .respond_with(status_code(400)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/BAR-2"))
+                .times(2)
+                .respond_with(status_code(204)),
+        );
+        // BAR's release was created by this run and goes, FOO's was already there and stays
+        server.expect(
+            Expectation::matching(request::method_path(
+                "POST",
+                "/rest/api/3/version/20/removeAndSwap",
+            ))
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "POST",
+                "/rest/api/3/version/10/removeAndSwap",
+            ))
+            .times(0)
+            .respond_with(status_code(204)),
+        );
+
+        let res = do_release(&ctx, &args);
+        assert!(matches!(res, Err(AppError::ReleaseRolledBack(1))));
+    }
+
+    #[test]
+    fn component_and_assignee_narrow_the_release_jql() {
+        let ctx = Cli::for_tests("http://localhost".to_string());
+        let args = ReleaseCompletedIssuesArgs {
+            component: Some(r#"api "v2" \ beta"#.to_string()),
+            assignee: Some("unassigned".to_string()),
+            ..args()
+        };
+        assert_eq!(
+            release_jql(&ctx, &args).unwrap(),
+            r#"(((project = FOO AND status = Done) and (fixVersion is EMPTY)) AND component = "api \"v2\" \\ beta") AND assignee is EMPTY"#
+        );
     }
 }
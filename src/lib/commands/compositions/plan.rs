@@ -0,0 +1,465 @@
+use super::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct PlanReleaseArgs {
+    #[arg(
+        long,
+        short,
+        required_unless_present_any = ["bump", "auto_bump"],
+        help = NAME_HELP
+    )]
+    pub release_name: Option<String>,
+
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub project: String,
+
+    #[arg(long, short, help = "JQL query to determine which issues to release")]
+    pub issue_jql: Option<String>,
+
+    #[arg(long, short, help = "only release issues with this component")]
+    pub component: Option<String>,
+
+    #[arg(
+        long,
+        help = "only release issues assigned to this user: an email address, display name, account id, `me` or `unassigned`"
+    )]
+    pub assignee: Option<String>,
+
+    #[arg(long, short, help = DESCRIPTION_HELP)]
+    pub description: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "release_name",
+        help = "name the release by incrementing the highest existing version instead of giving --release-name"
+    )]
+    pub bump: Option<Bump>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["release_name", "bump"],
+        help = "like --bump, but work out major/minor/patch from the types and labels of the released issues"
+    )]
+    pub auto_bump: bool,
+
+    #[arg(
+        long = "bump-rule",
+        value_parser = parse_bump_rule,
+        help = "extra --auto-bump rule, replacing any default for the same name ex type:Spike=minor, label:breaking-change=major"
+    )]
+    pub bump_rules: Vec<BumpRule>,
+
+    #[command(flatten)]
+    pub naming: ReleaseNameArgs,
+
+    #[arg(long, help = "file to write the plan to; printed to stdout if omitted")]
+    pub out: Option<PathBuf>,
+}
+
+impl PlanReleaseArgs {
+    /// The release these args select and name; atomicity and journaling are up to the apply.
+    fn release_args(&self) -> ReleaseCompletedIssuesArgs {
+        ReleaseCompletedIssuesArgs {
+            release_name: self.release_name.clone(),
+            project: self.project.clone(),
+            issue_jql: self.issue_jql.clone(),
+            component: self.component.clone(),
+            assignee: self.assignee.clone(),
+            description: self.description.clone(),
+            atomic: false,
+            journal: None,
+            resume: None,
+            bump: self.bump,
+            auto_bump: self.auto_bump,
+            bump_rules: self.bump_rules.clone(),
+            naming: self.naming.clone(),
+        }
+    }
+}
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ApplyReleasePlanArgs {
+    #[arg(help = "plan file written by plan-release")]
+    pub plan: PathBuf,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "all or nothing; if any issue cannot be tagged, undo the tagging, delete the new release and exit non-zero"
+    )]
+    pub atomic: bool,
+
+    #[arg(
+        long,
+        help = "record each step in this file so an interrupted apply can be resumed with release-all-completed-issues --resume"
+    )]
+    pub journal: Option<PathBuf>,
+}
+
+/// Everything `apply-release-plan` will do, fixed at plan time so it can be reviewed.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleasePlan {
+    pub(crate) project: String,
+    pub(crate) release_name: String,
+    /// The release's id in each project the issues belong to, by project key, or none where the
+    /// apply will create it.
+    pub(crate) releases: BTreeMap<String, Option<String>>,
+    pub(crate) description: Option<String>,
+    pub(crate) release_date: NaiveDate,
+    pub(crate) jql: String,
+    pub(crate) issues: Vec<String>,
+}
+
+pub fn execute_plan_release(ctx: &Cli, args: &PlanReleaseArgs) -> Result<(), AppError> {
+    let plan = do_plan_release(ctx, &args.release_args())?;
+    let contents = serde_json::to_string_pretty(&plan)
+        .map_err(|e| AppError::SerializationError(e.to_string()))?;
+    match &args.out {
+        Some(path) => fs::write(path, contents)?,
+        None => println!("{}", contents),
+    }
+    Ok(())
+}
+
+pub fn do_plan_release(
+    ctx: &Cli,
+    args: &ReleaseCompletedIssuesArgs,
+) -> Result<ReleasePlan, AppError> {
+    let jql = release_jql(ctx, args)?;
+    let issues = find_issue_keys(ctx, jql.clone())?;
+    if issues.is_empty() {
+        return Err(AppError::NoIssuesFound);
+    }
+    // unlike a release run, which picks up a release an earlier run left unreleased, a plan
+    // always names a new one
+    let release_name = match &args.release_name {
+        Some(name) => name.clone(),
+        None => releases::do_next_release_name(
+            ctx,
+            &args.project,
+            release_bump(ctx, args, &issues, &[])?,
+            &args.naming,
+        )?,
+    };
+    // other projects' releases are picked up like a release run would, but not --project's
+    let mut planned = BTreeMap::new();
+    for project in release_projects(&args.project, &issues) {
+        let existing = releases::find_release_by_name(ctx, project.clone(), release_name.clone())?;
+        if project == args.project && existing.is_some() {
+            return Err(AppError::PlanDrift(format!(
+                "release {} already exists",
+                release_name
+            )));
+        }
+        planned.insert(project, existing.map(|r| r.id));
+    }
+    Ok(ReleasePlan {
+        project: args.project.clone(),
+        release_name,
+        releases: planned,
+        description: args.description.clone(),
+        release_date: Utc::now().date_naive(),
+        jql,
+        issues,
+    })
+}
+
+pub fn execute_apply_release_plan(ctx: &Cli, args: &ApplyReleasePlanArgs) -> Result<(), AppError> {
+    let contents = fs::read_to_string(&args.plan)?;
+    let plan: ReleasePlan = serde_json::from_str(&contents)
+        .map_err(|e| AppError::InvalidPlan(format!("{}: {}", args.plan.display(), e)))?;
+    do_apply_release_plan(ctx, &plan, args)
+}
+
+/// Runs a plan exactly as written, refusing if jira no longer matches what was planned.
+pub fn do_apply_release_plan(
+    ctx: &Cli,
+    plan: &ReleasePlan,
+    args: &ApplyReleasePlanArgs,
+) -> Result<(), AppError> {
+    check_drift(ctx, plan)?;
+
+    let release_args = ReleaseCompletedIssuesArgs {
+        release_name: Some(plan.release_name.clone()),
+        project: plan.project.clone(),
+        issue_jql: Some(plan.jql.clone()),
+        component: None,
+        assignee: None,
+        description: plan.description.clone(),
+        atomic: args.atomic,
+        journal: args.journal.clone(),
+        resume: None,
+        bump: None,
+        auto_bump: false,
+        bump_rules: vec![],
+        naming: ReleaseNameArgs::default(),
+    };
+    let mut journal = ReleaseJournal::new(&release_args, args.journal.clone());
+    journal.issues = plan.issues.clone();
+    journal.save()?;
+    run_release(ctx, &release_args, &mut journal, plan.release_date)
+}
+
+fn check_drift(ctx: &Cli, plan: &ReleasePlan) -> Result<(), AppError> {
+    for (project, planned) in &plan.releases {
+        let current =
+            releases::find_release_by_name(ctx, project.clone(), plan.release_name.clone())?
+                .map(|r| r.id);
+        if current != *planned {
+            return Err(AppError::PlanDrift(match planned {
+                Some(id) => format!(
+                    "release {} in {} is no longer {}",
+                    plan.release_name, project, id
+                ),
+                None => format!(
+                    "release {} already exists in {}",
+                    plan.release_name, project
+                ),
+            }));
+        }
+    }
+
+    let planned: BTreeSet<String> = plan.issues.iter().cloned().collect();
+    let current: BTreeSet<String> = find_issue_keys(ctx, plan.jql.clone())?
+        .into_iter()
+        .collect();
+    if planned != current {
+        let added: Vec<&String> = current.difference(&planned).collect();
+        let removed: Vec<&String> = planned.difference(&current).collect();
+        return Err(AppError::PlanDrift(format!(
+            "issues matched by the plan's jql changed; new: {:?}, no longer matched: {:?}",
+            added, removed
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn plan() -> ReleasePlan {
+        ReleasePlan {
+            project: "FOO".to_string(),
+            release_name: "1.0".to_string(),
+            releases: BTreeMap::from([("FOO".to_string(), None)]),
+            description: None,
+            release_date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+            jql: "project = FOO".to_string(),
+            issues: vec!["FOO-1".to_string()],
+        }
+    }
+
+    fn no_releases() -> serde_json::Value {
+        serde_json::json!({"total": 0, "startAt": 0, "isLast": true, "values": []})
+    }
+
+    fn search_result(keys: &[&str]) -> serde_json::Value {
+        let issues: Vec<serde_json::Value> = keys
+            .iter()
+            .map(|k| {
+                serde_json::json!({
+                    "id": k,
+                    "key": k,
+                    "fields": {"summary": "", "status": {"id": "1", "name": "Done"}, "fixVersions": []}
+                })
+            })
+            .collect();
+        serde_json::json!({"total": keys.len(), "startAt": 0, "issues": issues})
+    }
+
+    #[test]
+    fn apply_refuses_when_new_issues_match() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(no_releases())),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search"))
+                .respond_with(json_encoded(search_result(&["FOO-1", "FOO-2"]))),
+        );
+
+        let args = ApplyReleasePlanArgs {
+            plan: PathBuf::new(),
+            atomic: false,
+            journal: None,
+        };
+        let res = do_apply_release_plan(&ctx, &plan(), &args);
+        assert!(matches!(res, Err(AppError::PlanDrift(_))));
+    }
+
+    #[test]
+    fn apply_runs_the_planned_release() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .times(2)
+                .respond_with(json_encoded(no_releases())),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search"))
+                .respond_with(json_encoded(search_result(&["FOO-1"]))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/version"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "name": "1.0",
+                    "project": "FOO",
+                    "releaseDate": "2023-01-31"
+                })))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "id": "10",
+                "name": "1.0",
+                "archived": false,
+                "released": false,
+                "projectId": 1
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/issue/FOO-1"))
+                .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/version/10"))
+                .respond_with(status_code(200)),
+        );
+
+        let args = ApplyReleasePlanArgs {
+            plan: PathBuf::new(),
+            atomic: false,
+            journal: None,
+        };
+        do_apply_release_plan(&ctx, &plan(), &args).unwrap();
+    }
+
+    #[test]
+    fn bumped_plan_names_a_release_that_does_not_exist_yet() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search"))
+                .respond_with(json_encoded(search_result(&["FOO-1"]))),
+        );
+        // 1.3.0 was created ahead of time and is not released yet
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .times(2)
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "12", "name": "1.2.0", "archived": false, "released": true, "projectId": 1},
+                        {"id": "13", "name": "1.3.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+
+        let args =
+            PlanReleaseArgs::try_parse_from(["plan-release", "-p", "FOO", "--bump", "minor"])
+                .unwrap();
+        let plan = do_plan_release(&ctx, &args.release_args()).unwrap();
+        assert_eq!(plan.release_name, "1.4.0");
+    }
+
+    #[test]
+    fn plan_records_each_projects_release() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search"))
+                .respond_with(json_encoded(search_result(&["FOO-1", "BAR-1"]))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(no_releases())),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/BAR/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "20", "name": "1.0", "archived": false, "released": false, "projectId": 2}
+                    ]
+                }))),
+        );
+
+        let args = PlanReleaseArgs::try_parse_from(["plan-release", "-p", "FOO", "-r", "1.0"])
+            .unwrap();
+        let plan = do_plan_release(&ctx, &args.release_args()).unwrap();
+        assert_eq!(
+            plan.releases,
+            BTreeMap::from([
+                ("BAR".to_string(), Some("20".to_string())),
+                ("FOO".to_string(), None)
+            ])
+        );
+    }
+
+    #[test]
+    fn apply_refuses_when_another_projects_release_appears() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/BAR/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "20", "name": "1.0", "archived": false, "released": false, "projectId": 2}
+                    ]
+                }))),
+        );
+
+        let plan = ReleasePlan {
+            releases: BTreeMap::from([("BAR".to_string(), None), ("FOO".to_string(), None)]),
+            issues: vec!["FOO-1".to_string(), "BAR-1".to_string()],
+            ..plan()
+        };
+        let args = ApplyReleasePlanArgs {
+            plan: PathBuf::new(),
+            atomic: false,
+            journal: None,
+        };
+        match do_apply_release_plan(&ctx, &plan, &args) {
+            Err(AppError::PlanDrift(e)) => assert_eq!(e, "release 1.0 already exists in BAR"),
+            other => panic!("{:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn unreadable_plans_say_why() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.json");
+        fs::write(&path, r#"{"project": "FOO"}"#).unwrap();
+        let ctx = Cli::for_tests("http://localhost".to_string());
+        let args = ApplyReleasePlanArgs {
+            plan: path,
+            atomic: false,
+            journal: None,
+        };
+        match execute_apply_release_plan(&ctx, &args) {
+            Err(AppError::InvalidPlan(e)) => assert!(e.contains("missing field"), "{}", e),
+            other => panic!("{:?}", other.err()),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use super::*;
+use releases::{BY_ID_HELP, RELEASE_HELP};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ReleaseStatusArgs {
+    #[arg(long, short, help = RELEASE_HELP)]
+    pub release: String,
+
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub project: String,
+
+    #[arg(long, short, help = BY_ID_HELP)]
+    pub by_id: Option<bool>,
+
+    #[arg(
+        long = "blocked-status",
+        default_value = "Blocked",
+        help = "status that counts as blocked; repeat for several"
+    )]
+    pub blocked_statuses: Vec<String>,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(i32).range(1..=255),
+        help = "exit code when the release still has unresolved or blocked issues"
+    )]
+    pub not_ready_exit_code: i32,
+}
+
+/// Everything needed to decide whether a release can be cut.
+#[derive(Serialize, Debug)]
+struct ReleaseStatusReport {
+    release: String,
+    id: String,
+    released: bool,
+    release_date: Option<NaiveDate>,
+    overdue: bool,
+    issues: u64,
+    fixed: u64,
+    affected: u64,
+    unresolved: u64,
+    to_do: usize,
+    in_progress: usize,
+    done: usize,
+    statuses: String,
+    blocked: String,
+    unassigned: String,
+    ready: bool,
+}
+
+pub fn execute_release_status(ctx: &Cli, args: &ReleaseStatusArgs) -> Result<(), AppError> {
+    let (report, not_ready) = do_release_status(ctx, args)?;
+    util::format_print(vec![report], ctx)?;
+    match not_ready {
+        Some(reasons) => Err(AppError::ReleaseNotReady(reasons, args.not_ready_exit_code)),
+        None => Ok(()),
+    }
+}
+
+/// Builds the report, along with why the release isn't ready if it isn't. A release is ready
+/// once none of its issues are unresolved or blocked.
+fn do_release_status(
+    ctx: &Cli,
+    args: &ReleaseStatusArgs,
+) -> Result<(ReleaseStatusReport, Option<String>), AppError> {
+    let id = releases::release_id(ctx, &args.project, &args.release, args.by_id)?;
+    let release = releases::do_get_release(ctx, &id)?;
+    let related = releases::do_related_issue_counts(ctx, &id)?;
+    let unresolved = releases::do_unresolved_issue_count(ctx, &id)?;
+    let issues = do_search_issues(
+        ctx,
+        &SearchIssuesArgs::all(format!("fixVersion = {} ORDER BY key", id)),
+    )?;
+
+    let mut statuses: BTreeMap<String, usize> = BTreeMap::new();
+    let (mut to_do, mut in_progress, mut done) = (0, 0, 0);
+    let mut blocked = vec![];
+    let mut unassigned = vec![];
+    for issue in &issues {
+        let status = &issue.fields.status;
+        *statuses.entry(status.name.clone()).or_default() += 1;
+        match status.status_category.as_ref().map(|c| c.key.as_str()) {
+            Some("done") => done += 1,
+            Some("indeterminate") => in_progress += 1,
+            _ => to_do += 1,
+        }
+        if args
+            .blocked_statuses
+            .iter()
+            .any(|b| b.eq_ignore_ascii_case(&status.name))
+        {
+            blocked.push(issue.key.clone());
+        }
+        if issue.fields.assignee.is_none() {
+            unassigned.push(issue.key.clone());
+        }
+    }
+
+    let mut reasons = vec![];
+    if unresolved.issues_unresolved_count > 0 {
+        reasons.push(format!(
+            "{} unresolved issue(s)",
+            unresolved.issues_unresolved_count
+        ));
+    }
+    if !blocked.is_empty() {
+        reasons.push(format!("blocked: {}", blocked.join(", ")));
+    }
+
+    let report = ReleaseStatusReport {
+        release: release.name.unwrap_or_default(),
+        id,
+        released: release.released,
+        release_date: release.release_date,
+        overdue: release.overdue.unwrap_or(false),
+        issues: unresolved.issues_count,
+        fixed: related.issues_fixed_count,
+        affected: related.issues_affected_count,
+        unresolved: unresolved.issues_unresolved_count,
+        to_do,
+        in_progress,
+        done,
+        statuses: statuses
+            .iter()
+            .map(|(name, count)| format!("{}: {}", name, count))
+            .collect::<Vec<String>>()
+            .join("|"),
+        blocked: blocked.join("|"),
+        unassigned: unassigned.join("|"),
+        ready: reasons.is_empty(),
+    };
+    let not_ready = if reasons.is_empty() {
+        None
+    } else {
+        Some(reasons.join("; "))
+    };
+    Ok((report, not_ready))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn issue(key: &str, status: &str, category: &str, assignee: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "id": key,
+            "key": key,
+            "fields": {
+                "summary": "",
+                "status": {"id": "1", "name": status, "statusCategory": {"key": category, "name": category}},
+                "fixVersions": [],
+                "assignee": assignee.map(|a| serde_json::json!({"accountId": a}))
+            }
+        })
+    }
+
+    #[test]
+    fn unresolved_and_blocked_issues_are_not_ready() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/version/10"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "id": "10",
+                    "name": "1.0",
+                    "archived": false,
+                    "released": false,
+                    "releaseDate": "2023-01-31",
+                    "overdue": true,
+                    "projectId": 1
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/version/10/relatedIssueCounts",
+            ))
+            .respond_with(json_encoded(serde_json::json!({
+                "issuesFixedCount": 3,
+                "issuesAffectedCount": 1,
+                "issueCountWithCustomFieldsShowingVersion": 0
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/version/10/unresolvedIssueCount",
+            ))
+            .respond_with(json_encoded(serde_json::json!({
+                "issuesUnresolvedCount": 2,
+                "issuesCount": 3
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search")).respond_with(
+                json_encoded(serde_json::json!({
+                    "total": 3,
+                    "startAt": 0,
+                    "issues": [
+                        issue("FOO-1", "Done", "done", Some("abc")),
+                        issue("FOO-2", "Blocked", "indeterminate", Some("abc")),
+                        issue("FOO-3", "To Do", "new", None)
+                    ]
+                })),
+            ),
+        );
+
+        let args = ReleaseStatusArgs {
+            release: "10".to_string(),
+            project: "FOO".to_string(),
+            by_id: Some(true),
+            blocked_statuses: vec!["Blocked".to_string()],
+            not_ready_exit_code: 3,
+        };
+        let (report, not_ready) = do_release_status(&ctx, &args).unwrap();
+        assert_eq!((report.to_do, report.in_progress, report.done), (1, 1, 1));
+        assert_eq!(report.blocked, "FOO-2");
+        assert_eq!(report.unassigned, "FOO-3");
+        assert_eq!(report.statuses, "Blocked: 1|Done: 1|To Do: 1");
+        assert!(report.overdue);
+        assert!(!report.ready);
+        assert_eq!(
+            not_ready.as_deref(),
+            Some("2 unresolved issue(s); blocked: FOO-2")
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::lib::commands::users::{do_myself, User};
+use crate::lib::{util, AppError};
+use crate::Cli;
+use clap::Parser;
+use std::collections::HashMap;
+
+const BROWSE: &str = "BROWSE_PROJECTS";
+const ADMINISTER: &str = "ADMINISTER_PROJECTS";
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct DoctorArgs {
+    #[arg(
+        long,
+        short,
+        help = "also check the user can browse and administer this project; project key or id"
+    )]
+    pub(crate) project: Option<String>,
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pass,
+    Fail,
+    /// not tried because a check it depends on failed
+    Skip,
+}
+
+/// One line of the checklist.
+#[derive(Serialize, Debug)]
+pub struct Check {
+    pub(crate) check: String,
+    pub(crate) outcome: Outcome,
+    pub(crate) detail: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ServerInfo {
+    version: String,
+    deployment_type: Option<String>,
+    server_title: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MyPermissions {
+    permissions: HashMap<String, Permission>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Permission {
+    have_permission: bool,
+}
+
+pub fn execute_doctor(ctx: &Cli, args: &DoctorArgs) -> Result<(), AppError> {
+    let checks = do_doctor(ctx, args);
+    let failed = checks.iter().filter(|c| c.outcome == Outcome::Fail).count();
+    util::format_print(checks, ctx)?;
+    match failed {
+        0 => Ok(()),
+        n => Err(AppError::ChecksFailed(n)),
+    }
+}
+
+/// Runs every check in turn, skipping those that can't work once an earlier one has failed.
+pub fn do_doctor(ctx: &Cli, args: &DoctorArgs) -> Vec<Check> {
+    let mut checks = vec![];
+    let info_url = format!("{}/rest/api/3/serverInfo", ctx.base_jira_url);
+    // some sites only answer serverInfo once logged in
+    let info = match util::do_get_anonymous::<ServerInfo>(&info_url) {
+        Err(AppError::ApiCallBadStatus(status)) if is_auth_status(&status) => {
+            util::do_get::<ServerInfo, HashMap<&str, String>>(&info_url, ctx, HashMap::new())
+        }
+        info => info,
+    };
+    let reachable = match info {
+        Ok(info) => {
+            checks.push(pass("base url is reachable", ctx.base_jira_url.clone()));
+            checks.push(pass(
+                "base url is jira",
+                info.server_title.unwrap_or_else(|| "jira".to_string()),
+            ));
+            checks.push(match info.deployment_type.as_deref() {
+                Some("Cloud") => pass("jira version", format!("{} (Cloud)", info.version)),
+                other => fail(
+                    "jira version",
+                    format!(
+                        "{} ({}); this tool uses the cloud rest api v3",
+                        info.version,
+                        other.unwrap_or("unknown deployment")
+                    ),
+                ),
+            });
+            true
+        }
+        Err(AppError::ApiCallFailed(e)) => {
+            checks.push(fail(
+                "base url is reachable",
+                format!("could not connect to {}: {}", ctx.base_jira_url, e),
+            ));
+            checks.push(skip("base url is jira"));
+            checks.push(skip("jira version"));
+            false
+        }
+        Err(AppError::ApiCallBadStatus(status)) if is_auth_status(&status) => {
+            // jira, most likely, but one that won't say so to these credentials
+            checks.push(pass("base url is reachable", ctx.base_jira_url.clone()));
+            for check in ["base url is jira", "jira version"] {
+                checks.push(Check {
+                    check: check.to_string(),
+                    outcome: Outcome::Skip,
+                    detail: format!("serverInfo needs a login and refused this one ({})", status),
+                });
+            }
+            true
+        }
+        Err(e) => {
+            checks.push(pass("base url is reachable", ctx.base_jira_url.clone()));
+            checks.push(fail(
+                "base url is jira",
+                format!(
+                    "serverInfo did not answer like jira ({}); check the base url",
+                    e
+                ),
+            ));
+            checks.push(skip("jira version"));
+            false
+        }
+    };
+
+    let authenticated = if reachable {
+        match do_myself(ctx) {
+            Ok(user) => {
+                checks.push(pass("credentials are accepted", describe(&user)));
+                true
+            }
+            Err(e) => {
+                checks.push(fail("credentials are accepted", auth_failure(&e)));
+                false
+            }
+        }
+    } else {
+        checks.push(skip("credentials are accepted"));
+        false
+    };
+
+    if let Some(project) = &args.project {
+        let browse = format!("can browse {}", project);
+        let administer = format!("can administer {}", project);
+        if !authenticated {
+            checks.push(skip(&browse));
+            checks.push(skip(&administer));
+            return checks;
+        }
+        match do_my_permissions(ctx, project) {
+            Ok(permissions) => {
+                let has = |key: &str| permissions.get(key).is_some_and(|p| p.have_permission);
+                checks.push(if has(BROWSE) {
+                    pass(&browse, BROWSE.to_string())
+                } else {
+                    fail(&browse, format!("missing {}", BROWSE))
+                });
+                checks.push(if has(ADMINISTER) {
+                    pass(&administer, ADMINISTER.to_string())
+                } else {
+                    fail(
+                        &administer,
+                        format!("missing {}, needed to manage releases", ADMINISTER),
+                    )
+                });
+            }
+            Err(e) => {
+                let detail = format!(
+                    "no project {}, or it is hidden from this user ({})",
+                    project, e
+                );
+                checks.push(fail(&browse, detail));
+                checks.push(skip(&administer));
+            }
+        }
+    }
+    checks
+}
+
+fn do_my_permissions(ctx: &Cli, project: &str) -> Result<HashMap<String, Permission>, AppError> {
+    let req_url = format!("{}/rest/api/3/mypermissions", ctx.base_jira_url);
+    let mut query_params = HashMap::<&str, String>::new();
+    let by = if !project.is_empty() && project.chars().all(|c| c.is_ascii_digit()) {
+        "projectId"
+    } else {
+        "projectKey"
+    };
+    query_params.insert(by, project.to_string());
+    query_params.insert("permissions", format!("{},{}", BROWSE, ADMINISTER));
+    Ok(
+        util::do_get::<MyPermissions, HashMap<&str, String>>(&req_url, ctx, query_params)?
+            .permissions,
+    )
+}
+
+fn describe(user: &User) -> String {
+    let name = user.display_name.as_deref().unwrap_or(&user.account_id);
+    match &user.email_address {
+        Some(email) => format!("as {} <{}>", name, email),
+        None => format!("as {}", name),
+    }
+}
+
+fn is_auth_status(status: &str) -> bool {
+    status.ends_with("401") || status.ends_with("403")
+}
+
+/// Turns the bare status code into the likely cause.
+fn auth_failure(e: &AppError) -> String {
+    match e {
+        AppError::ApiCallBadStatus(status) if status.ends_with("401") => {
+            "jira rejected the user email and token (401); check both, and that the token has not expired".to_string()
+        }
+        AppError::ApiCallBadStatus(status) if status.ends_with("403") => {
+            "the user may not use the api (403); the account may need a captcha cleared or be deactivated".to_string()
+        }
+        e => e.to_string(),
+    }
+}
+
+fn pass(check: &str, detail: String) -> Check {
+    Check {
+        check: check.to_string(),
+        outcome: Outcome::Pass,
+        detail,
+    }
+}
+
+fn fail(check: &str, detail: String) -> Check {
+    Check {
+        check: check.to_string(),
+        outcome: Outcome::Fail,
+        detail,
+    }
+}
+
+fn skip(check: &str) -> Check {
+    Check {
+        check: check.to_string(),
+        outcome: Outcome::Skip,
+        detail: "an earlier check failed".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn outcomes(checks: &[Check]) -> Vec<(&str, Outcome)> {
+        checks
+            .iter()
+            .map(|c| (c.check.as_str(), c.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn passes_with_good_credentials_and_permissions() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/serverInfo"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "version": "1001.0.0-SNAPSHOT",
+                    "deploymentType": "Cloud",
+                    "serverTitle": "Jira"
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/myself")).respond_with(
+                json_encoded(serde_json::json!({
+                    "accountId": "abc",
+                    "displayName": "Release Bot",
+                    "active": true
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/mypermissions"),
+                request::query(url_decoded(contains(("projectKey", "FOO")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "permissions": {
+                    "BROWSE_PROJECTS": {"key": "BROWSE_PROJECTS", "havePermission": true},
+                    "ADMINISTER_PROJECTS": {"key": "ADMINISTER_PROJECTS", "havePermission": false}
+                }
+            }))),
+        );
+
+        let checks = do_doctor(
+            &ctx,
+            &DoctorArgs {
+                project: Some("FOO".to_string()),
+            },
+        );
+        assert_eq!(
+            outcomes(&checks),
+            vec![
+                ("base url is reachable", Outcome::Pass),
+                ("base url is jira", Outcome::Pass),
+                ("jira version", Outcome::Pass),
+                ("credentials are accepted", Outcome::Pass),
+                ("can browse FOO", Outcome::Pass),
+                ("can administer FOO", Outcome::Fail),
+            ]
+        );
+        assert_eq!(checks[3].detail, "as Release Bot");
+    }
+
+    #[test]
+    fn explains_a_rejected_token() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/serverInfo"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "version": "1001.0.0-SNAPSHOT",
+                    "deploymentType": "Cloud"
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/myself"))
+                .respond_with(status_code(401)),
+        );
+
+        let checks = do_doctor(
+            &ctx,
+            &DoctorArgs {
+                project: Some("FOO".to_string()),
+            },
+        );
+        assert_eq!(
+            outcomes(&checks)[3..],
+            [
+                ("credentials are accepted", Outcome::Fail),
+                ("can browse FOO", Outcome::Skip),
+                ("can administer FOO", Outcome::Skip),
+            ]
+        );
+        assert!(checks[3].detail.contains("401"));
+    }
+
+    #[test]
+    fn logs_in_for_server_info_and_checks_a_project_by_id() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/serverInfo"),
+                request::headers(not(contains(key("authorization")))),
+            ])
+            .respond_with(status_code(401)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/serverInfo"),
+                request::headers(contains(key("authorization"))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "version": "1001.0.0-SNAPSHOT",
+                "deploymentType": "Cloud"
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/myself")).respond_with(
+                json_encoded(serde_json::json!({"accountId": "abc", "active": true})),
+            ),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/mypermissions"),
+                request::query(url_decoded(contains(("projectId", "10000")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "permissions": {
+                    "BROWSE_PROJECTS": {"key": "BROWSE_PROJECTS", "havePermission": true},
+                    "ADMINISTER_PROJECTS": {"key": "ADMINISTER_PROJECTS", "havePermission": true}
+                }
+            }))),
+        );
+
+        let checks = do_doctor(
+            &ctx,
+            &DoctorArgs {
+                project: Some("10000".to_string()),
+            },
+        );
+        assert!(
+            checks.iter().all(|c| c.outcome == Outcome::Pass),
+            "{:?}",
+            checks
+        );
+    }
+}
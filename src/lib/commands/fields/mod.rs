@@ -0,0 +1,133 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+pub use resolver::*;
+
+use crate::lib::{util, AppError};
+use crate::Cli;
+use clap::Parser;
+use std::collections::HashMap;
+
+mod resolver;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) custom: bool,
+    pub(crate) schema: Option<FieldSchema>,
+}
+
+/// What kind of value a field holds, ex `number`, `array` of `string`, or `option`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSchema {
+    #[serde(rename = "type")]
+    pub(crate) field_type: String,
+    pub(crate) items: Option<String>,
+    pub(crate) custom: Option<String>,
+}
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ListFieldsArgs {
+    #[arg(
+        long,
+        short,
+        help = "optionally filter results; case insensitive substring match in the name and id"
+    )]
+    pub(crate) filter: Option<String>,
+    #[arg(long, default_value_t = false, help = "only list custom fields")]
+    pub(crate) custom_only: bool,
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "fetch the fields from jira even if the cached list is still fresh"
+    )]
+    pub(crate) refresh: bool,
+}
+
+/// A field as listed; the schema flattened so it fits in a csv row.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FieldRow {
+    id: String,
+    name: String,
+    custom: bool,
+    #[serde(rename = "type")]
+    field_type: Option<String>,
+    items: Option<String>,
+}
+
+pub fn execute_list_fields(ctx: &Cli, args: &ListFieldsArgs) -> Result<(), AppError> {
+    let resolver = if args.refresh {
+        FieldResolver::refresh(ctx)?
+    } else {
+        FieldResolver::load(ctx)?
+    };
+    let filter = args.filter.as_ref().map(|f| f.to_lowercase());
+    let rows: Vec<FieldRow> = resolver
+        .fields()
+        .iter()
+        .filter(|f| !args.custom_only || f.custom)
+        .filter(|f| {
+            filter.as_ref().is_none_or(|filter| {
+                f.name.to_lowercase().contains(filter) || f.id.to_lowercase().contains(filter)
+            })
+        })
+        .map(|f| FieldRow {
+            id: f.id.clone(),
+            name: f.name.clone(),
+            custom: f.custom,
+            field_type: f.schema.as_ref().map(|s| s.field_type.clone()),
+            items: f.schema.as_ref().and_then(|s| s.items.clone()),
+        })
+        .collect();
+    util::format_print(rows, ctx)
+}
+
+/// Every field, system and custom, straight from jira.
+pub fn do_list_fields(ctx: &Cli) -> Result<Vec<Field>, AppError> {
+    let req_url = format!("{}/rest/api/3/field", ctx.base_jira_url);
+    util::do_get::<Vec<Field>, HashMap<&str, String>>(&req_url, ctx, HashMap::new())
+}
+
+/// A `--set` argument: a field, by name or id, and the value to give it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldAssignment {
+    pub(crate) field: String,
+    pub(crate) value: String,
+}
+
+pub const SET_FIELD_HELP: &str =
+    "set a field, by name or id, ex \"Story Points=3\"; repeat for several";
+
+/// The `--set` values keyed by field id, ready to send as an issue's `fields`.
+pub fn field_values(
+    ctx: &Cli,
+    assignments: &[FieldAssignment],
+) -> Result<serde_json::Map<String, serde_json::Value>, AppError> {
+    let mut values = serde_json::Map::new();
+    if assignments.is_empty() {
+        return Ok(values);
+    }
+    let resolver = FieldResolver::load(ctx)?;
+    for assignment in assignments {
+        let (id, value) = resolver.value_for(assignment)?;
+        values.insert(id, value);
+    }
+    Ok(values)
+}
+
+/// Parses `name=value` for `--set`.
+pub fn parse_field_assignment(input: &str) -> Result<FieldAssignment, String> {
+    match input.split_once('=') {
+        Some((field, value)) if !field.trim().is_empty() => Ok(FieldAssignment {
+            field: field.trim().to_string(),
+            value: value.to_string(),
+        }),
+        _ => Err(format!("'{}' should look like field=value", input)),
+    }
+}
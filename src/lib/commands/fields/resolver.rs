@@ -0,0 +1,238 @@
+use super::*;
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, warn};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How long the cached field list is used before it is fetched again.
+const FIELD_CACHE_TTL_HOURS: i64 = 24;
+
+/// The field list as written to the cache file.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FieldCache {
+    fetched_at: DateTime<Utc>,
+    fields: Vec<Field>,
+}
+
+/// Maps field names to ids and knows each field's schema, so commands can take names like
+/// "Story Points" where jira wants customfield_10016.
+#[derive(Debug)]
+pub struct FieldResolver {
+    fields: Vec<Field>,
+}
+
+impl FieldResolver {
+    /// The cached field list for this jira, fetching it again once the cache is stale.
+    pub fn load(ctx: &Cli) -> Result<FieldResolver, AppError> {
+        FieldResolver::load_from(ctx, &cache_path(ctx), Utc::now())
+    }
+
+    /// Fetches the field list from jira and caches it, however fresh the cache is.
+    pub fn refresh(ctx: &Cli) -> Result<FieldResolver, AppError> {
+        FieldResolver::fetch(ctx, &cache_path(ctx), Utc::now())
+    }
+
+    pub(crate) fn load_from(
+        ctx: &Cli,
+        path: &Path,
+        now: DateTime<Utc>,
+    ) -> Result<FieldResolver, AppError> {
+        let cached = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<FieldCache>(&contents).ok());
+        match cached {
+            Some(cache) if now - cache.fetched_at < Duration::hours(FIELD_CACHE_TTL_HOURS) => {
+                debug!("using cached fields from {}", path.display());
+                Ok(FieldResolver {
+                    fields: cache.fields,
+                })
+            }
+            _ => FieldResolver::fetch(ctx, path, now),
+        }
+    }
+
+    fn fetch(ctx: &Cli, path: &Path, now: DateTime<Utc>) -> Result<FieldResolver, AppError> {
+        let cache = FieldCache {
+            fetched_at: now,
+            fields: do_list_fields(ctx)?,
+        };
+        // a cache that can't be written only costs a fetch next time
+        if let Err(e) = write_cache(path, &cache) {
+            warn!("could not cache fields in {}: {}", path.display(), e);
+        }
+        Ok(FieldResolver {
+            fields: cache.fields,
+        })
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// The field with this id, or failing that the one with this name, ignoring case.
+    pub fn resolve(&self, name_or_id: &str) -> Result<&Field, AppError> {
+        if let Some(field) = self.fields.iter().find(|f| f.id == name_or_id) {
+            return Ok(field);
+        }
+        let named: Vec<&Field> = self
+            .fields
+            .iter()
+            .filter(|f| f.name.eq_ignore_ascii_case(name_or_id))
+            .collect();
+        match named.as_slice() {
+            [field] => Ok(field),
+            [] => Err(AppError::UnknownField(name_or_id.to_string())),
+            _ => Err(AppError::AmbiguousField(
+                name_or_id.to_string(),
+                named
+                    .iter()
+                    .map(|f| f.id.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+            )),
+        }
+    }
+
+    /// The field id and the value in the shape jira expects for the field's schema.
+    pub fn value_for(&self, assignment: &FieldAssignment) -> Result<(String, Value), AppError> {
+        let field = self.resolve(&assignment.field)?;
+        let value = match &field.schema {
+            Some(schema) if schema.field_type == "array" => Value::Array(
+                assignment
+                    .value
+                    .split(',')
+                    .map(|v| v.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(|v| typed_value(&field.name, schema.items.as_deref(), v))
+                    .collect::<Result<Vec<Value>, AppError>>()?,
+            ),
+            Some(schema) => typed_value(
+                &field.name,
+                Some(schema.field_type.as_str()),
+                &assignment.value,
+            )?,
+            None => Value::String(assignment.value.clone()),
+        };
+        Ok((field.id.clone(), value))
+    }
+}
+
+fn typed_value(field: &str, field_type: Option<&str>, value: &str) -> Result<Value, AppError> {
+    Ok(match field_type {
+        Some("number") => {
+            let number: serde_json::Number = value.trim().parse().map_err(|_| {
+                AppError::InvalidFieldValue(format!("{} takes a number, not '{}'", field, value))
+            })?;
+            Value::Number(number)
+        }
+        Some("option") => serde_json::json!({ "value": value }),
+        Some("user") => serde_json::json!({ "accountId": value }),
+        Some("priority" | "issuetype" | "resolution" | "version" | "component" | "project") => {
+            serde_json::json!({ "name": value })
+        }
+        _ => Value::String(value.to_string()),
+    })
+}
+
+fn write_cache(path: &Path, cache: &FieldCache) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let contents =
+        serde_json::to_string(cache).map_err(|e| AppError::SerializationError(e.to_string()))?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// One cache file per jira instance, under the user's cache directory.
+fn cache_path(ctx: &Cli) -> PathBuf {
+    let dir = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir);
+    let instance: String = ctx
+        .base_jira_url
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    dir.join("jira-automation")
+        .join(format!("fields-{}.json", instance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn fields() -> serde_json::Value {
+        serde_json::json!([
+            {"id": "summary", "name": "Summary", "custom": false, "schema": {"type": "string"}},
+            {"id": "labels", "name": "Labels", "custom": false, "schema": {"type": "array", "items": "string"}},
+            {"id": "customfield_10016", "name": "Story Points", "custom": true, "schema": {"type": "number"}},
+            {"id": "customfield_10020", "name": "Team", "custom": true, "schema": {"type": "option"}},
+            {"id": "customfield_10021", "name": "Team", "custom": true, "schema": {"type": "string"}}
+        ])
+    }
+
+    #[test]
+    fn caches_until_stale() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fields.json");
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/field"))
+                .times(2)
+                .respond_with(json_encoded(fields())),
+        );
+
+        let now = Utc::now();
+        FieldResolver::load_from(&ctx, &path, now).unwrap();
+        // fresh, so served from the cache
+        let resolver = FieldResolver::load_from(&ctx, &path, now + Duration::hours(1)).unwrap();
+        assert_eq!(resolver.fields().len(), 5);
+        // stale, so fetched again
+        FieldResolver::load_from(&ctx, &path, now + Duration::hours(25)).unwrap();
+    }
+
+    #[test]
+    fn resolves_names_to_typed_values() {
+        let resolver = FieldResolver {
+            fields: serde_json::from_value(fields()).unwrap(),
+        };
+        let set = |field: &str, value: &str| {
+            resolver.value_for(&FieldAssignment {
+                field: field.to_string(),
+                value: value.to_string(),
+            })
+        };
+        assert_eq!(
+            set("story points", "3").unwrap(),
+            ("customfield_10016".to_string(), serde_json::json!(3))
+        );
+        assert_eq!(
+            set("Labels", "a, b").unwrap(),
+            ("labels".to_string(), serde_json::json!(["a", "b"]))
+        );
+        assert_eq!(
+            set("customfield_10020", "Core").unwrap(),
+            (
+                "customfield_10020".to_string(),
+                serde_json::json!({"value": "Core"})
+            )
+        );
+        assert!(matches!(
+            set("Story Points", "lots"),
+            Err(AppError::InvalidFieldValue(_))
+        ));
+        assert!(matches!(
+            set("Team", "x"),
+            Err(AppError::AmbiguousField(_, _))
+        ));
+        assert!(matches!(set("Sprint", "x"), Err(AppError::UnknownField(_))));
+    }
+}
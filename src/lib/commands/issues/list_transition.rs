@@ -22,7 +22,7 @@ pub fn execute_list_transitions(
     args: &ListIssueTransitionsArgs,
 ) -> Result<(), AppError> {
     let values = do_list_transitions(ctx, args)?;
-    util::format_print::<Transition>(values, ctx.output_format)?;
+    util::format_print::<Transition>(values, ctx)?;
 
     Ok(())
 }
@@ -3,6 +3,7 @@ mod search_issues;
 mod transition_issue;
 mod update;
 
+use crate::lib::util::Page;
 pub use list_transition::*;
 pub use search_issues::*;
 use serde::{Deserialize, Serialize};
@@ -32,16 +33,48 @@ pub struct Transition {
 pub struct IssueStatus {
     description: Option<String>,
     id: String,
+    pub(crate) name: String,
+    pub(crate) status_category: Option<StatusCategory>,
+}
+
+/// The to do / in progress / done bucket a status belongs to.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCategory {
+    /// one of new, indeterminate or done
+    pub(crate) key: String,
     name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueUser {
+    pub(crate) account_id: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) email_address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueType {
+    id: String,
+    pub(crate) name: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct IssueFields {
-    summary: String,
-    status: IssueStatus,
+    pub(crate) summary: String,
+    pub(crate) status: IssueStatus,
     description: Option<IssueDescription>,
     fix_versions: Vec<IssueRelease>,
+    pub(crate) issuetype: Option<IssueType>,
+    pub(crate) assignee: Option<IssueUser>,
+    #[serde(default)]
+    pub(crate) labels: Vec<String>,
+    /// any fields asked for with `--field`, keyed by id
+    #[serde(flatten)]
+    pub(crate) extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -76,7 +109,7 @@ pub struct IssueRelease {
 pub struct Issue {
     id: String,
     pub(crate) key: String,
-    fields: IssueFields,
+    pub(crate) fields: IssueFields,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -86,3 +119,12 @@ pub struct PaginatedIssues {
     pub(crate) start_at: u64,
     pub(crate) issues: Vec<Issue>,
 }
+
+impl Page<Issue> for PaginatedIssues {
+    fn into_values(self) -> Vec<Issue> {
+        self.issues
+    }
+    fn total(&self) -> Option<u64> {
+        Some(self.total)
+    }
+}
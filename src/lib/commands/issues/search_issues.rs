@@ -1,6 +1,7 @@
-use crate::lib::commands::issues::{Issue, IssueDescriptionContent, PaginatedIssues};
-use crate::lib::util::Format;
-use crate::lib::util::Format::Json;
+use crate::lib::commands::fields::FieldResolver;
+use crate::lib::commands::issues::{Issue, PaginatedIssues};
+use crate::lib::commands::users;
+use crate::lib::util::{PageRequest, Paginator, MAX_ITEMS_HELP};
 use crate::lib::{util, AppError};
 use crate::Cli;
 use clap::Parser;
@@ -10,6 +11,16 @@ use serde::Serialize;
 #[command()]
 pub struct SearchIssuesArgs {
     pub jql: String,
+    #[arg(
+        long = "field",
+        help = "also return this field, by name or id, ex \"Story Points\"; repeat for several"
+    )]
+    pub(crate) fields: Vec<String>,
+    #[arg(
+        long,
+        help = "only issues assigned to this user: an email address, display name, account id, `me` or `unassigned`"
+    )]
+    pub(crate) assignee: Option<String>,
     #[arg(
     long,
     short,
@@ -21,6 +32,33 @@ pub struct SearchIssuesArgs {
     pub(crate) page_size: u64,
     #[arg(long, default_value_t = 0, help = "item index to begin paging at")]
     pub(crate) page_start_idx: u64,
+    #[arg(long, help = MAX_ITEMS_HELP)]
+    pub(crate) max_items: Option<u64>,
+}
+
+impl Default for SearchIssuesArgs {
+    fn default() -> Self {
+        SearchIssuesArgs {
+            jql: String::new(),
+            fields: vec![],
+            assignee: None,
+            unpaginate: false,
+            page_size: 50,
+            page_start_idx: 0,
+            max_items: None,
+        }
+    }
+}
+
+impl SearchIssuesArgs {
+    /// Every issue `jql` matches, with the default fields.
+    pub(crate) fn all(jql: String) -> SearchIssuesArgs {
+        SearchIssuesArgs {
+            jql,
+            unpaginate: true,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -29,13 +67,7 @@ pub struct SearchIssuesRequest {
     jql: String,
     start_at: u64,
     max_results: u64,
-    fields: Vec<&'static str>,
-}
-
-impl SearchIssuesRequest {
-    fn set_start_idx(&mut self, new: u64) {
-        self.start_at = new;
-    }
+    fields: Vec<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -46,74 +78,161 @@ struct CsvCompatibleIssue {
     pub description: String,
     pub status: String,
     pub fix_versions: String,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-pub fn execute_search_issues(cli: &Cli, args: &SearchIssuesArgs) -> Result<(), AppError> {
-    let results = do_search_issues(cli, args)?;
-
-    match cli.output_format {
-        Format::Csv => {
-            let csvresults: Vec<CsvCompatibleIssue> = results
+impl From<&Issue> for CsvCompatibleIssue {
+    fn from(r: &Issue) -> Self {
+        CsvCompatibleIssue {
+            key: r.key.clone(),
+            id: r.id.clone(),
+            summary: r.fields.summary.clone(),
+            description: r.fields.description.as_ref().and_then(|d| d.content.as_ref().and_then(|c|
+                c.first()
+                    .and_then(|desc_content|
+                        desc_content.content.as_ref().and_then(|c|
+                            c.first().and_then(|content_content|
+                                content_content.text.clone()))))).unwrap_or("".to_string()),
+            status: r.fields.status.name.clone(),
+            fix_versions: r
+                .fields
+                .fix_versions
                 .iter()
-                .map(|r| CsvCompatibleIssue {
-                    key: r.key.clone(),
-                    id: r.id.clone(),
-                    summary: r.fields.summary.clone(),
-                    description: r.fields.description.as_ref().and_then(|d| d.content.as_ref().and_then(|c|
-                        c.first()
-                            .and_then(|desc_content|
-                                desc_content.content.as_ref().and_then(|c|
-                                    c.first().and_then(|content_content|
-                                        content_content.text.clone()))))).unwrap_or("".to_string()),
-                    status: r.fields.status.name.clone(),
-                    fix_versions: r
-                        .fields
-                        .fix_versions
-                        .iter()
-                        .map(|v| v.name.clone())
-                        .collect::<Vec<String>>()
-                        .join("|"),
-                })
-                .collect();
-            util::format_print(csvresults, cli.output_format)
+                .map(|v| v.name.clone())
+                .collect::<Vec<String>>()
+                .join("|"),
+            extra: r.fields.extra.clone(),
         }
-        Json => util::format_print(results, cli.output_format),
+    }
+}
+
+pub fn execute_search_issues(cli: &Cli, args: &SearchIssuesArgs) -> Result<(), AppError> {
+    let (args, names) = with_field_ids(cli, args)?;
+    let results = iter_search_issues(cli, &args).map(move |r| r.map(|i| named_fields(i, &names)));
+
+    if cli.output_format.is_tabular() && cli.columns.is_none() {
+        util::format_stream(
+            results.map(|r| r.map(|issue| CsvCompatibleIssue::from(&issue))),
+            cli,
+        )
+    } else {
+        util::format_stream(results, cli)
     }
 }
 
 pub fn do_search_issues(cli: &Cli, args: &SearchIssuesArgs) -> Result<Vec<Issue>, AppError> {
-    let mut req = SearchIssuesRequest {
-        jql: args.jql.clone(),
-        start_at: args.page_start_idx,
-        max_results: args.page_size,
-        fields: vec!["summary", "status", "fixVersions", "description"],
-    };
-    page_loop(cli, &mut req)
+    let (args, names) = with_field_ids(cli, args)?;
+    iter_search_issues(cli, &args)
+        .map(|r| r.map(|i| named_fields(i, &names)))
+        .collect()
 }
 
-fn page_loop(ctx: &Cli, request: &mut SearchIssuesRequest) -> Result<Vec<Issue>, AppError> {
-    let url = format!("{}/rest/api/3/search", ctx.base_jira_url);
-    let result = util::do_post::<PaginatedIssues, SearchIssuesRequest>(&url, ctx, request)?;
-    let mut issues: Vec<Issue> = vec![];
-    if let Some(mut x) = result {
-        let remainder = x.total % request.max_results;
-        let mut pages = x.total / request.max_results;
-        if remainder > 0 {
-            pages += 1;
-        }
-        issues.append(&mut x.issues);
-        for idx in 1..pages {
-            request.set_start_idx(idx);
-            let newpage = util::do_post::<PaginatedIssues, SearchIssuesRequest>(
-                &url.to_string(),
-                ctx,
-                request,
-            )?;
-            if let Some(mut page) = newpage {
-                issues.append(&mut page.issues);
-            }
-        }
+/// The args with `--assignee` folded into the jql and `--field` names swapped for ids, plus
+/// each id's name to show it under.
+fn with_field_ids(
+    cli: &Cli,
+    args: &SearchIssuesArgs,
+) -> Result<(SearchIssuesArgs, Vec<(String, String)>), AppError> {
+    let mut args = args.clone();
+    if let Some(assignee) = args.assignee.take() {
+        args.jql = format!("({}) AND {}", args.jql, users::assignee_jql(cli, &assignee)?);
+    }
+    if args.fields.is_empty() {
+        return Ok((args, vec![]));
+    }
+    let resolver = FieldResolver::load(cli)?;
+    let mut names = vec![];
+    for field in &args.fields {
+        let field = resolver.resolve(field)?;
+        names.push((field.id.clone(), field.name.clone()));
     }
+    args.fields = names.iter().map(|(id, _)| id.clone()).collect();
+    Ok((args, names))
+}
 
-    Ok(issues)
+/// Keys the extra fields by name rather than id, in the order they were asked for. Fields that
+/// are always fetched, such as labels, are taken from where they were deserialized to.
+fn named_fields(mut issue: Issue, names: &[(String, String)]) -> Issue {
+    if names.is_empty() {
+        return issue;
+    }
+    let all = serde_json::to_value(&issue.fields).unwrap_or_default();
+    let mut extra = serde_json::Map::new();
+    for (id, name) in names {
+        let value = all.get(id).cloned().unwrap_or(serde_json::Value::Null);
+        extra.insert(name.clone(), value);
+    }
+    issue.fields.extra = extra;
+    issue
+}
+
+/// Yields issues as each page of search results arrives; `args.fields` must already be ids.
+pub(crate) fn iter_search_issues<'a>(
+    cli: &'a Cli,
+    args: &SearchIssuesArgs,
+) -> impl Iterator<Item = Result<Issue, AppError>> + 'a {
+    let url = format!("{}/rest/api/3/search", cli.base_jira_url);
+    let jql = args.jql.clone();
+    let mut fields: Vec<String> = [
+        "summary",
+        "status",
+        "fixVersions",
+        "description",
+        "issuetype",
+        "labels",
+        "assignee",
+    ]
+    .iter()
+    .map(|f| f.to_string())
+    .collect();
+    fields.extend(args.fields.iter().cloned());
+    Paginator::new(
+        args.page_start_idx,
+        args.page_size,
+        args.unpaginate,
+        move |page: &PageRequest| {
+            let request = SearchIssuesRequest {
+                jql: jql.clone(),
+                start_at: page.start_at,
+                max_results: page.max_results,
+                fields: fields.clone(),
+            };
+            util::do_post::<PaginatedIssues, SearchIssuesRequest>(&url, cli, &request)?
+                .ok_or(AppError::DeserializationError)
+        },
+    )
+    .max_items(args.max_items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_fields_include_the_default_fields() {
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "key": "FOO-1",
+            "fields": {
+                "summary": "fix it",
+                "status": {"id": "1", "name": "Done"},
+                "fixVersions": [],
+                "labels": ["api"],
+                "customfield_10016": 3
+            }
+        }))
+        .unwrap();
+        let names = [
+            ("customfield_10016".to_string(), "Story Points".to_string()),
+            ("labels".to_string(), "Labels".to_string()),
+            ("summary".to_string(), "Summary".to_string()),
+        ];
+
+        let issue = named_fields(issue, &names);
+        assert_eq!(
+            serde_json::Value::Object(issue.fields.extra),
+            serde_json::json!({"Story Points": 3, "Labels": ["api"], "Summary": "fix it"})
+        );
+    }
 }
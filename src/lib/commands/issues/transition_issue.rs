@@ -1,4 +1,5 @@
 use super::*;
+use crate::lib::commands::fields::{self, FieldAssignment, SET_FIELD_HELP};
 use crate::lib::commands::issues::Transition;
 use crate::lib::{util, AppError};
 use crate::Cli;
@@ -13,11 +14,15 @@ pub struct TransitionIssueArgs {
     pub(crate) transition: String,
     #[arg(long, short, help = BY_ID_HELP, default_value_t = false)]
     pub(crate) use_transition_id: bool,
+    #[arg(long, value_parser = fields::parse_field_assignment, help = SET_FIELD_HELP)]
+    pub(crate) set: Vec<FieldAssignment>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct TransitionIssueRequest {
     pub(crate) transition: ReqTransition,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub(crate) fields: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -31,12 +36,19 @@ pub fn execute_transition_issue(ctx: &Cli, args: &TransitionIssueArgs) -> Result
     } else {
         id = get_transition_id(ctx, args)?;
     }
-    do_transition(ctx, id, args.name.clone())
+    let fields = fields::field_values(ctx, &args.set)?;
+    do_transition(ctx, id, args.name.clone(), fields)
 }
 
-fn do_transition(ctx: &Cli, transition_id: String, issue: String) -> Result<(), AppError> {
+fn do_transition(
+    ctx: &Cli,
+    transition_id: String,
+    issue: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), AppError> {
     let req = TransitionIssueRequest {
         transition: ReqTransition { id: transition_id },
+        fields,
     };
     let req_url = format!(
         "{}/rest/api/3/issue/{}/transitions",
@@ -1,4 +1,6 @@
 use super::*;
+use crate::lib::commands::fields::{self, FieldAssignment, SET_FIELD_HELP};
+use crate::lib::commands::users::{self, ASSIGNEE_HELP};
 use crate::lib::{util, AppError};
 use crate::Cli;
 use clap::ArgGroup;
@@ -6,7 +8,7 @@ use clap::Parser;
 use std::collections::HashMap;
 
 #[derive(Parser, Clone)]
-#[command(group(ArgGroup::new("vers").required(true).args(["related_version", "fix_version"]),))]
+#[command(group(ArgGroup::new("vers").required(true).multiple(true).args(["related_version", "fix_version", "set", "assignee"]),))]
 pub struct UpdateIssueArgs {
     #[arg(long, short, help = ISSUE_NAME_HELP)]
     pub(crate) name: String,
@@ -16,11 +18,19 @@ pub struct UpdateIssueArgs {
     pub(crate) related_version: Option<String>,
     #[arg(long, short, help = BY_ID_HELP, default_value_t = false)]
     pub(crate) use_version_id: bool,
+    #[arg(long, value_parser = fields::parse_field_assignment, help = SET_FIELD_HELP)]
+    pub(crate) set: Vec<FieldAssignment>,
+    #[arg(long, help = ASSIGNEE_HELP)]
+    pub(crate) assignee: Option<String>,
 }
 
+type FieldOperations = Vec<HashMap<String, HashMap<String, String>>>;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UpdateIssueRequest {
-    update: HashMap<String, Vec<HashMap<String, HashMap<String, String>>>>,
+    update: HashMap<String, FieldOperations>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    fields: serde_json::Map<String, serde_json::Value>,
 }
 
 pub fn execute_update_issue(ctx: &Cli, args: &UpdateIssueArgs) -> Result<(), AppError> {
@@ -30,7 +40,15 @@ pub fn execute_update_issue(ctx: &Cli, args: &UpdateIssueArgs) -> Result<(), App
 fn do_update(ctx: &Cli, args: &UpdateIssueArgs) -> Result<(), AppError> {
     let mut req = UpdateIssueRequest {
         update: HashMap::new(),
+        fields: fields::field_values(ctx, &args.set)?,
     };
+    if let Some(assignee) = &args.assignee {
+        let account_id = users::resolve_assignee(ctx, assignee)?;
+        req.fields.insert(
+            "assignee".to_string(),
+            serde_json::json!({ "accountId": account_id }),
+        );
+    }
     if args.fix_version.is_some() {
         if args.use_version_id {
             req.update.insert(
@@ -77,3 +95,79 @@ fn do_update(ctx: &Cli, args: &UpdateIssueArgs) -> Result<(), AppError> {
     util::do_put::<(), UpdateIssueRequest>(&req_url, ctx, &req)?;
     Ok(())
 }
+
+/// Takes a fix version back off an issue, e.g. when undoing a release.
+pub(crate) fn do_remove_fix_version(
+    ctx: &Cli,
+    issue: &str,
+    version_id: &str,
+) -> Result<(), AppError> {
+    let req = UpdateIssueRequest {
+        update: HashMap::from([(
+            "fixVersions".to_string(),
+            Vec::from([HashMap::from([(
+                "remove".to_string(),
+                HashMap::from([("id".to_string(), version_id.to_string())]),
+            )])]),
+        )]),
+        fields: serde_json::Map::new(),
+    };
+    let req_url = format!("{}/rest/api/3/issue/{}", ctx.base_jira_url, issue);
+    util::do_put::<(), UpdateIssueRequest>(&req_url, ctx, &req)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_can_be_set_alongside_a_version() {
+        let args = UpdateIssueArgs::try_parse_from([
+            "update-issue",
+            "-n",
+            "FOO-1",
+            "-f",
+            "1.0",
+            "--set",
+            "X=1",
+        ])
+        .unwrap();
+        assert_eq!(args.fix_version.as_deref(), Some("1.0"));
+        assert_eq!(args.set.len(), 1);
+    }
+
+    #[test]
+    fn assignee_combines_with_other_updates() {
+        let args = UpdateIssueArgs::try_parse_from([
+            "update-issue",
+            "-n",
+            "FOO-1",
+            "--assignee",
+            "me",
+            "--set",
+            "X=1",
+        ])
+        .unwrap();
+        assert_eq!(args.assignee.as_deref(), Some("me"));
+        assert_eq!(args.set.len(), 1);
+
+        let args = UpdateIssueArgs::try_parse_from([
+            "update-issue",
+            "-n",
+            "FOO-1",
+            "-f",
+            "1.0",
+            "--assignee",
+            "me",
+        ])
+        .unwrap();
+        assert_eq!(args.fix_version.as_deref(), Some("1.0"));
+        assert_eq!(args.assignee.as_deref(), Some("me"));
+    }
+
+    #[test]
+    fn something_to_update_is_required() {
+        assert!(UpdateIssueArgs::try_parse_from(["update-issue", "-n", "FOO-1"]).is_err());
+    }
+}
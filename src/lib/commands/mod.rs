@@ -1,3 +1,9 @@
+pub mod components;
 pub mod compositions;
+pub mod doctor;
+pub mod fields;
 pub mod issues;
+pub mod pipelines;
+pub mod projects;
 pub mod releases;
+pub mod users;
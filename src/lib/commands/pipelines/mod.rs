@@ -0,0 +1,355 @@
+mod template;
+
+use crate::lib::commands::issues::{
+    do_search_issues, execute_transition_issue, execute_update_issue, SearchIssuesArgs,
+    TransitionIssueArgs, UpdateIssueArgs,
+};
+use crate::lib::commands::releases::{
+    do_create_release, do_update_release, CreateReleaseArgs, UpdateReleaseArgs,
+};
+use crate::lib::{util, AppError};
+use crate::Cli;
+use clap::{CommandFactory, Parser};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use template::{as_text, evaluate_condition, interpolate, interpolate_value};
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct RunPipelineArgs {
+    #[arg(help = "yaml file describing the steps to run")]
+    pub pipeline: PathBuf,
+    #[arg(
+        long = "var",
+        value_parser = parse_var,
+        help = "set or override a pipeline variable ex --var version=1.2.0"
+    )]
+    pub vars: Vec<(String, String)>,
+}
+
+fn parse_var(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .ok_or_else(|| format!("expected key=value, got '{}'", s))
+}
+
+/// A pipeline file: variables plus the steps to run in order.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    #[serde(default)]
+    vars: Map<String, Value>,
+    steps: Vec<Step>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Step {
+    /// name later steps use to refer to this step's output, as `${steps.<id>.output}`
+    id: Option<String>,
+    action: Action,
+    /// arguments, named like the cli flags of the matching command
+    #[serde(default)]
+    with: Map<String, Value>,
+    #[serde(rename = "if")]
+    condition: Option<String>,
+    /// run the step once per element of this list, exposed as `${item}`
+    for_each: Option<String>,
+    #[serde(default)]
+    on_error: OnError,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum Action {
+    CreateRelease,
+    UpdateRelease,
+    SearchIssues,
+    UpdateIssue,
+    TransitionIssue,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum OnError {
+    /// stop the pipeline and exit non-zero
+    #[default]
+    Fail,
+    /// record the failure and carry on with the next step
+    Continue,
+}
+
+#[derive(Serialize, Debug)]
+struct StepReport {
+    step: String,
+    action: String,
+    status: &'static str,
+    error: Option<String>,
+}
+
+pub fn execute_run_pipeline(ctx: &Cli, args: &RunPipelineArgs) -> Result<(), AppError> {
+    let contents = fs::read_to_string(&args.pipeline)?;
+    let mut pipeline: Pipeline = serde_yaml::from_str(&contents)
+        .map_err(|e| AppError::InvalidPipeline(e.to_string()))?;
+    for (k, v) in &args.vars {
+        pipeline.vars.insert(k.clone(), Value::String(v.clone()));
+    }
+    let (report, result) = do_run_pipeline(ctx, &pipeline);
+    util::format_print(report, ctx)?;
+    result
+}
+
+/// Runs every step, returning a per-step report alongside the overall result.
+fn do_run_pipeline(ctx: &Cli, pipeline: &Pipeline) -> (Vec<StepReport>, Result<(), AppError>) {
+    let mut scope = Value::Object(pipeline.vars.clone());
+    scope["steps"] = Value::Object(Map::new());
+    let mut report = vec![];
+
+    for (idx, step) in pipeline.steps.iter().enumerate() {
+        let name = step.id.clone().unwrap_or_else(|| format!("step-{}", idx + 1));
+        let action = serde_json::to_value(step.action)
+            .ok()
+            .map(|a| as_text(&a))
+            .unwrap_or_default();
+
+        let outcome = match &step.condition {
+            Some(condition) => evaluate_condition(condition, &scope)
+                .map_err(AppError::InvalidPipeline)
+                .and_then(|run| {
+                    if run {
+                        run_step(ctx, step, &mut scope).map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                }),
+            None => run_step(ctx, step, &mut scope).map(Some),
+        };
+
+        let (status, error, output) = match outcome {
+            Ok(Some(output)) => ("ok", None, output),
+            Ok(None) => {
+                info!("skipping step {0}, its condition is false", name);
+                ("skipped", None, Value::Null)
+            }
+            Err(e) => ("failed", Some(e.to_string()), Value::Null),
+        };
+        let count = match &output {
+            Value::Array(list) => list.len(),
+            Value::Null => 0,
+            _ => 1,
+        };
+        scope["steps"][&name] = serde_json::json!({
+            "output": output,
+            "count": count,
+            "status": status,
+            "error": error,
+        });
+        report.push(StepReport {
+            step: name.clone(),
+            action,
+            status,
+            error: error.clone(),
+        });
+
+        if let Some(e) = error {
+            if step.on_error == OnError::Fail {
+                return (report, Err(AppError::PipelineStepFailed(name, e)));
+            }
+            warn!("step {0} failed, continuing: {1}", name, e);
+        }
+    }
+    (report, Ok(()))
+}
+
+fn run_step(ctx: &Cli, step: &Step, scope: &mut Value) -> Result<Value, AppError> {
+    let Some(for_each) = &step.for_each else {
+        return run_action(ctx, step, scope);
+    };
+    let items = match interpolate(for_each, scope).map_err(AppError::InvalidPipeline)? {
+        Value::Array(items) => items,
+        other => {
+            return Err(AppError::InvalidPipeline(format!(
+                "for_each must be a list, got {}",
+                other
+            )))
+        }
+    };
+    let mut outputs = vec![];
+    for item in items {
+        scope["item"] = item;
+        let output = run_action(ctx, step, scope);
+        if let Some(map) = scope.as_object_mut() {
+            map.remove("item");
+        }
+        outputs.push(output?);
+    }
+    Ok(Value::Array(outputs))
+}
+
+fn run_action(ctx: &Cli, step: &Step, scope: &Value) -> Result<Value, AppError> {
+    let with = interpolate_value(&Value::Object(step.with.clone()), scope)
+        .map_err(AppError::InvalidPipeline)?;
+    let with = with.as_object().cloned().unwrap_or_default();
+
+    match step.action {
+        Action::CreateRelease => {
+            let release = do_create_release(ctx, &parse_args::<CreateReleaseArgs>(&with)?)?;
+            to_output(&release)
+        }
+        Action::UpdateRelease => {
+            do_update_release(ctx, &parse_args::<UpdateReleaseArgs>(&with)?)?;
+            Ok(Value::Null)
+        }
+        Action::SearchIssues => {
+            let issues = do_search_issues(ctx, &parse_args::<SearchIssuesArgs>(&with)?)?;
+            to_output(&issues)
+        }
+        Action::UpdateIssue => {
+            execute_update_issue(ctx, &parse_args::<UpdateIssueArgs>(&with)?)?;
+            Ok(Value::Null)
+        }
+        Action::TransitionIssue => {
+            execute_transition_issue(ctx, &parse_args::<TransitionIssueArgs>(&with)?)?;
+            Ok(Value::Null)
+        }
+    }
+}
+
+fn to_output<T: Serialize>(value: &T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::SerializationError(e.to_string()))
+}
+
+/// Builds a command's args from a step's `with` block by handing clap the equivalent command
+/// line, so steps get the same validation and defaults as the cli.
+fn parse_args<T: Parser + CommandFactory>(with: &Map<String, Value>) -> Result<T, AppError> {
+    let command = T::command();
+    let mut argv: Vec<String> = vec!["step".to_string()];
+    for (key, value) in with {
+        let flag = key.replace('_', "-");
+        let arg = command
+            .get_arguments()
+            .find(|a| a.get_long() == Some(flag.as_str()) || a.get_id() == key.as_str())
+            .ok_or_else(|| AppError::InvalidPipeline(format!("unknown argument '{}'", key)))?;
+        let text = as_text(value);
+        match arg.get_long() {
+            Some(long) if !arg.get_action().takes_values() => {
+                if text == "true" {
+                    argv.push(format!("--{}", long));
+                }
+            }
+            Some(long) => {
+                argv.push(format!("--{}", long));
+                argv.push(text);
+            }
+            None => argv.push(text),
+        }
+    }
+    T::try_parse_from(argv).map_err(|e| AppError::InvalidPipeline(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn parse_args_maps_flags_and_positionals() {
+        let with: Map<String, serde_json::Value> = serde_json::from_value(serde_json::json!({
+            "jql": "project = FOO",
+            "unpaginate": true,
+            "page_size": 10
+        }))
+        .unwrap();
+        let args = parse_args::<SearchIssuesArgs>(&with).unwrap();
+        assert_eq!(args.jql, "project = FOO");
+        assert!(args.unpaginate);
+        assert_eq!(args.page_size, 10);
+    }
+
+    #[test]
+    fn outputs_flow_between_steps() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+vars:
+  project: FOO
+steps:
+  - id: create
+    action: create-release
+    with:
+      project: ${project}
+      name: "1.0"
+  - id: search
+    action: search-issues
+    with:
+      jql: project = ${project}
+  - action: update-issue
+    for_each: ${steps.search.output}
+    with:
+      name: ${item.key}
+      fix_version: ${steps.create.output.id}
+      use_version_id: true
+  - action: transition-issue
+    if: ${steps.search.count} > 5
+    with:
+      name: FOO-1
+      transition: Done
+  - action: update-release
+    on_error: continue
+    with:
+      project: ${project}
+      release: ${steps.create.output.id}
+      by_id: true
+      is_released: true
+"#,
+        )
+        .unwrap();
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "id": "10",
+                    "name": "1.0",
+                    "archived": false,
+                    "released": false,
+                    "projectId": 1
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/rest/api/3/search"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 1,
+                    "startAt": 0,
+                    "issues": [{
+                        "id": "1",
+                        "key": "FOO-1",
+                        "fields": {"summary": "", "status": {"id": "1", "name": "Done"}, "fixVersions": []}
+                    }]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "update": {"fixVersions": [{"add": {"id": "10"}}]}
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/rest/api/3/version/10"))
+                .respond_with(status_code(500)),
+        );
+
+        let (report, result) = do_run_pipeline(&ctx, &pipeline);
+        assert!(result.is_ok());
+        let statuses: Vec<&str> = report.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, vec!["ok", "ok", "ok", "skipped", "failed"]);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::lib::util::value_at_path;
+use serde_json::Value;
+
+/// Replaces every `${path}` in `text` with the value found at that dotted path in `scope`.
+/// A string that is exactly one `${path}` keeps the referenced value's type, so lists and
+/// numbers survive being passed between steps.
+pub(crate) fn interpolate(text: &str, scope: &Value) -> Result<Value, String> {
+    let trimmed = text.trim();
+    if trimmed.starts_with("${") && trimmed.ends_with('}') && trimmed.matches("${").count() == 1 {
+        return lookup(&trimmed[2..trimmed.len() - 1], scope).cloned();
+    }
+
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unterminated ${{ in '{}'", text))?;
+        out.push_str(&as_text(lookup(&rest[start + 2..start + end], scope)?));
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+/// Interpolates every string inside a value, leaving its structure alone.
+pub(crate) fn interpolate_value(value: &Value, scope: &Value) -> Result<Value, String> {
+    match value {
+        Value::String(s) => interpolate(s, scope),
+        Value::Array(list) => list
+            .iter()
+            .map(|v| interpolate_value(v, scope))
+            .collect::<Result<Vec<Value>, String>>()
+            .map(Value::Array),
+        Value::Object(map) => {
+            let mut out = serde_json::Map::new();
+            for (k, v) in map {
+                out.insert(k.clone(), interpolate_value(v, scope)?);
+            }
+            Ok(Value::Object(out))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+/// Evaluates a step's `if`. After interpolation it is either a single value, which is false
+/// when empty, `false`, `0` or `null`, or a comparison using `==`, `!=`, `<`, `<=`, `>` or `>=`.
+pub(crate) fn evaluate_condition(condition: &str, scope: &Value) -> Result<bool, String> {
+    for op in ["==", "!=", "<=", ">=", "<", ">"] {
+        if let Some((left, right)) = condition.split_once(op) {
+            let left = as_text(&interpolate(left.trim(), scope)?);
+            let right = as_text(&interpolate(right.trim(), scope)?);
+            return compare(&left, op, &right);
+        }
+    }
+    let value = as_text(&interpolate(condition, scope)?);
+    Ok(!matches!(value.trim(), "" | "false" | "0" | "null"))
+}
+
+fn compare(left: &str, op: &str, right: &str) -> Result<bool, String> {
+    match op {
+        "==" => Ok(left == right),
+        "!=" => Ok(left != right),
+        _ => {
+            let (l, r) = match (left.parse::<f64>(), right.parse::<f64>()) {
+                (Ok(l), Ok(r)) => (l, r),
+                _ => return Err(format!("cannot compare '{}' {} '{}' as numbers", left, op, right)),
+            };
+            Ok(match op {
+                "<" => l < r,
+                "<=" => l <= r,
+                ">" => l > r,
+                _ => l >= r,
+            })
+        }
+    }
+}
+
+fn lookup<'a>(path: &str, scope: &'a Value) -> Result<&'a Value, String> {
+    value_at_path(scope, path.trim()).ok_or_else(|| format!("unknown variable '{}'", path.trim()))
+}
+
+pub(crate) fn as_text(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scope() -> Value {
+        json!({
+            "project": "FOO",
+            "steps": {
+                "create": {"output": {"id": "10"}, "count": 1},
+                "search": {"output": [{"key": "FOO-1"}, {"key": "FOO-2"}], "count": 2}
+            }
+        })
+    }
+
+    #[test]
+    fn interpolates_inside_strings() {
+        assert_eq!(
+            interpolate("project = ${project} AND fixVersion = ${steps.create.output.id}", &scope()),
+            Ok(json!("project = FOO AND fixVersion = 10"))
+        );
+    }
+
+    #[test]
+    fn whole_references_keep_their_type() {
+        assert_eq!(
+            interpolate("${steps.search.output}", &scope()),
+            Ok(json!([{"key": "FOO-1"}, {"key": "FOO-2"}]))
+        );
+        assert_eq!(interpolate("${steps.search.count}", &scope()), Ok(json!(2)));
+    }
+
+    #[test]
+    fn unknown_variables_are_errors() {
+        assert!(interpolate("${nope}", &scope()).is_err());
+    }
+
+    #[test]
+    fn conditions() {
+        assert_eq!(evaluate_condition("${steps.search.count} > 0", &scope()), Ok(true));
+        assert_eq!(evaluate_condition("${project} == BAR", &scope()), Ok(false));
+        assert_eq!(evaluate_condition("${project} != BAR", &scope()), Ok(true));
+        assert_eq!(evaluate_condition("${steps.create.count}", &scope()), Ok(true));
+        assert_eq!(evaluate_condition("false", &scope()), Ok(false));
+    }
+}
@@ -0,0 +1,20 @@
+use super::*;
+use clap::Parser;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct GetProjectArgs {
+    #[arg(long, short, help = PROJECT_KEY_HELP)]
+    pub(crate) project: String,
+}
+
+pub fn execute_get_project(ctx: &Cli, args: &GetProjectArgs) -> Result<(), AppError> {
+    let project = do_get_project(ctx, &args.project)?;
+    util::format_print(vec![project], ctx)
+}
+
+/// Fetches a project, including its issue types, by key or id.
+pub fn do_get_project(ctx: &Cli, project: &str) -> Result<Project, AppError> {
+    let req_url = format!("{}/rest/api/3/project/{}", ctx.base_jira_url, project);
+    util::do_get::<Project, HashMap<&str, String>>(&req_url, ctx, HashMap::new())
+}
@@ -0,0 +1,140 @@
+use super::*;
+use crate::lib::util::{PageBean, PageRequest, Paginator, MAX_ITEMS_HELP};
+use clap::{Parser, ValueEnum};
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum ProjectType {
+    Software,
+    Business,
+    ServiceDesk,
+}
+
+impl ProjectType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ProjectType::Software => "software",
+            ProjectType::Business => "business",
+            ProjectType::ServiceDesk => "service_desk",
+        }
+    }
+}
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ListProjectsArgs {
+    #[arg(
+        long,
+        short,
+        help = "optionally filter results; substring match in the key and name fields"
+    )]
+    pub(crate) filter: Option<String>,
+    #[arg(
+        long,
+        short,
+        help = "only projects in this project category, by name or id"
+    )]
+    pub(crate) category: Option<String>,
+    #[arg(long = "type", short, value_enum, help = "only projects of this type")]
+    pub(crate) project_type: Option<ProjectType>,
+    #[arg(
+        long,
+        short,
+        default_value_t = false,
+        help = "automatically query until all pages have been obtained"
+    )]
+    pub(crate) unpaginate: bool,
+    #[arg(long, default_value_t = 50, help = "how many items to return")]
+    pub(crate) page_size: u64,
+    #[arg(long, default_value_t = 0, help = "item index to begin paging at")]
+    pub(crate) page_start_idx: u64,
+    #[arg(long, help = MAX_ITEMS_HELP)]
+    pub(crate) max_items: Option<u64>,
+}
+
+pub fn execute_list_projects(ctx: &Cli, args: &ListProjectsArgs) -> Result<(), AppError> {
+    let category = match &args.category {
+        Some(category) => Some(category_id(ctx, category)?),
+        None => None,
+    };
+    util::format_stream(iter_projects(ctx, args, category), ctx)
+}
+
+/// Yields projects as each page arrives; `category_id` is the already looked up category.
+fn iter_projects<'a>(
+    ctx: &'a Cli,
+    args: &ListProjectsArgs,
+    category_id: Option<String>,
+) -> impl Iterator<Item = Result<Project, AppError>> + 'a {
+    let req_url = format!("{}/rest/api/3/project/search", ctx.base_jira_url);
+    let margs = args.clone();
+    Paginator::new(
+        args.page_start_idx,
+        args.page_size,
+        args.unpaginate,
+        move |page: &PageRequest| {
+            let mut query_params = HashMap::<&str, String>::new();
+            query_params.insert("startAt", page.start_at.to_string());
+            query_params.insert("maxResults", page.max_results.to_string());
+            if let Some(filter) = &margs.filter {
+                query_params.insert("query", filter.clone());
+            }
+            if let Some(category) = &category_id {
+                query_params.insert("categoryId", category.clone());
+            }
+            if let Some(project_type) = margs.project_type {
+                query_params.insert("typeKey", project_type.as_str().to_string());
+            }
+            util::do_get::<PageBean<Project>, HashMap<&str, String>>(&req_url, ctx, query_params)
+        },
+    )
+    .max_items(args.max_items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn filters_by_category_name_and_type() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/projectCategory"))
+                .respond_with(json_encoded(serde_json::json!([
+                    {"id": "10", "name": "Product"}
+                ]))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/project/search"),
+                request::query(url_decoded(contains(("categoryId", "10")))),
+                request::query(url_decoded(contains(("typeKey", "service_desk")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "total": 1,
+                "startAt": 0,
+                "isLast": true,
+                "values": [{"id": "1", "key": "HELP", "name": "Help desk", "projectTypeKey": "service_desk"}]
+            }))),
+        );
+
+        let args = ListProjectsArgs {
+            filter: None,
+            category: Some("product".to_string()),
+            project_type: Some(ProjectType::ServiceDesk),
+            unpaginate: true,
+            page_size: 50,
+            page_start_idx: 0,
+            max_items: None,
+        };
+        let category = category_id(&ctx, args.category.as_ref().unwrap()).unwrap();
+        let projects: Vec<Project> = iter_projects(&ctx, &args, Some(category))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].key, "HELP");
+    }
+}
@@ -0,0 +1,152 @@
+use super::*;
+use crate::lib::util::{PageBean, PageRequest, Paginator};
+use clap::Parser;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct ProjectMetadataArgs {
+    #[arg(long, short, help = PROJECT_KEY_HELP)]
+    pub(crate) project: String,
+}
+
+/// The statuses available to one issue type, as `/project/{key}/statuses` returns them.
+#[derive(Deserialize, Debug)]
+struct IssueTypeStatuses {
+    name: String,
+    statuses: Vec<ProjectStatus>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ProjectStatus {
+    id: String,
+    name: String,
+    description: Option<String>,
+    status_category: Option<StatusCategory>,
+}
+
+/// A status along with every issue type that uses it.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusRow {
+    id: String,
+    name: String,
+    category: Option<String>,
+    description: Option<String>,
+    issue_types: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Priority {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) status_color: Option<String>,
+    pub(crate) is_default: Option<bool>,
+}
+
+pub fn execute_list_issue_types(ctx: &Cli, args: &ProjectMetadataArgs) -> Result<(), AppError> {
+    util::format_print(do_get_project(ctx, &args.project)?.issue_types, ctx)
+}
+
+pub fn execute_list_statuses(ctx: &Cli, args: &ProjectMetadataArgs) -> Result<(), AppError> {
+    util::format_print(do_list_statuses(ctx, &args.project)?, ctx)
+}
+
+pub fn execute_list_priorities(ctx: &Cli, args: &ProjectMetadataArgs) -> Result<(), AppError> {
+    util::format_print(do_list_priorities(ctx, &args.project)?, ctx)
+}
+
+/// Every status used in the project, once each, in the order jira first lists them.
+pub fn do_list_statuses(ctx: &Cli, project: &str) -> Result<Vec<StatusRow>, AppError> {
+    let req_url = format!(
+        "{}/rest/api/3/project/{}/statuses",
+        ctx.base_jira_url, project
+    );
+    let by_type = util::do_get::<Vec<IssueTypeStatuses>, HashMap<&str, String>>(
+        &req_url,
+        ctx,
+        HashMap::new(),
+    )?;
+
+    let mut rows: Vec<(StatusRow, Vec<String>)> = vec![];
+    for issue_type in by_type {
+        for status in issue_type.statuses {
+            match rows.iter_mut().find(|(row, _)| row.id == status.id) {
+                Some((_, types)) => types.push(issue_type.name.clone()),
+                None => rows.push((
+                    StatusRow {
+                        id: status.id,
+                        name: status.name,
+                        category: status.status_category.map(|c| c.key),
+                        description: status.description,
+                        issue_types: String::new(),
+                    },
+                    vec![issue_type.name.clone()],
+                )),
+            }
+        }
+    }
+    Ok(rows
+        .into_iter()
+        .map(|(mut row, types)| {
+            row.issue_types = types.join("|");
+            row
+        })
+        .collect())
+}
+
+/// The priorities the project's priority scheme allows.
+pub fn do_list_priorities(ctx: &Cli, project: &str) -> Result<Vec<Priority>, AppError> {
+    let project_id = do_get_project(ctx, project)?.id;
+    let req_url = format!("{}/rest/api/3/priority/search", ctx.base_jira_url);
+    Paginator::new(0, 50, true, |page: &PageRequest| {
+        let mut query_params = HashMap::<&str, String>::new();
+        query_params.insert("projectId", project_id.clone());
+        query_params.insert("startAt", page.start_at.to_string());
+        query_params.insert("maxResults", page.max_results.to_string());
+        util::do_get::<PageBean<Priority>, HashMap<&str, String>>(&req_url, ctx, query_params)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn lists_each_status_once_with_its_issue_types() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        let status = |id: &str, name: &str, category: &str| serde_json::json!({"id": id, "name": name, "statusCategory": {"key": category, "name": category}});
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/FOO/statuses",
+            ))
+            .respond_with(json_encoded(serde_json::json!([
+                {"id": "1", "name": "Bug", "statuses": [status("1", "To Do", "new"), status("3", "Done", "done")]},
+                {"id": "2", "name": "Story", "statuses": [status("1", "To Do", "new"), status("2", "In Review", "indeterminate")]}
+            ]))),
+        );
+
+        let rows = do_list_statuses(&ctx, "FOO").unwrap();
+        let summary: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|r| (r.name.as_str(), r.issue_types.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("To Do", "Bug|Story"),
+                ("Done", "Bug"),
+                ("In Review", "Story")
+            ]
+        );
+        assert_eq!(rows[2].category.as_deref(), Some("indeterminate"));
+    }
+}
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+pub use get::*;
+pub use list::*;
+pub use metadata::*;
+
+use crate::lib::commands::issues::{IssueUser, StatusCategory};
+use crate::lib::{util, AppError};
+use crate::Cli;
+use std::collections::HashMap;
+
+mod get;
+mod list;
+mod metadata;
+
+pub const PROJECT_KEY_HELP: &str = "project key or id";
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub(crate) id: String,
+    pub(crate) key: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) project_type_key: Option<String>,
+    pub(crate) project_category: Option<ProjectCategory>,
+    pub(crate) lead: Option<IssueUser>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) issue_types: Vec<ProjectIssueType>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCategory {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectIssueType {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) subtask: bool,
+    pub(crate) hierarchy_level: Option<i64>,
+}
+
+/// The id of a project category, given by its id or its name.
+pub(crate) fn category_id(ctx: &Cli, category: &str) -> Result<String, AppError> {
+    let req_url = format!("{}/rest/api/3/projectCategory", ctx.base_jira_url);
+    util::do_get::<Vec<ProjectCategory>, HashMap<&str, String>>(&req_url, ctx, HashMap::new())?
+        .into_iter()
+        .find(|c| c.id == category || c.name.eq_ignore_ascii_case(category))
+        .map(|c| c.id)
+        .ok_or_else(|| AppError::ProjectCategoryNotFound(category.to_string()))
+}
@@ -2,6 +2,7 @@ use super::*;
 use crate::lib::commands::releases::Release;
 use crate::lib::{util, AppError};
 use crate::Cli;
+use chrono::NaiveDate;
 use clap::Parser;
 use std::collections::HashMap;
 
@@ -14,32 +15,32 @@ pub struct CreateReleaseArgs {
     pub(crate) name: String,
     #[arg(long, short, help = DESCRIPTION_HELP)]
     pub(crate) description: Option<String>,
-    #[arg(long, short, help = START_DATE_HELP)]
-    pub(crate) start_date: Option<String>,
-    #[arg(long, short, help = RELEASE_DATE_HELP)]
-    pub(crate) release_date: Option<String>,
+    #[arg(long, short, value_parser = util::parse_date, help = START_DATE_HELP)]
+    pub(crate) start_date: Option<NaiveDate>,
+    #[arg(long, short, value_parser = util::parse_date, help = RELEASE_DATE_HELP)]
+    pub(crate) release_date: Option<NaiveDate>,
 }
 
 fn assemble_create_args(args: CreateReleaseArgs) -> HashMap<&'static str, String> {
     let mut params: HashMap<&str, String> = HashMap::new();
     params.insert("name", args.name);
     params.insert("project", args.project);
-    if args.description.is_some() {
-        params.insert("description", args.description.unwrap());
+    if let Some(description) = args.description {
+        params.insert("description", description);
     }
-    if args.start_date.is_some() {
-        params.insert("startDate", args.start_date.unwrap());
+    if let Some(start_date) = args.start_date {
+        params.insert("startDate", start_date.to_string());
     }
-    if args.release_date.is_some() {
-        params.insert("releaseDate", args.release_date.unwrap());
+    if let Some(release_date) = args.release_date {
+        params.insert("releaseDate", release_date.to_string());
     }
     params
 }
 
 pub fn execute_create_release(ctx: &Cli, args: &CreateReleaseArgs) -> Result<(), AppError> {
     let result = do_create_release(ctx, args)?;
-    if result.is_some() {
-        util::format_print::<Release>(Vec::from([result.unwrap()]), ctx.output_format)?;
+    if let Some(release) = result {
+        util::format_print::<Release>(Vec::from([release]), ctx)?;
     }
 
     Ok(())
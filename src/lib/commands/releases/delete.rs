@@ -39,7 +39,7 @@ impl DeleteReleaseArgs {
 
 pub fn execute_delete_release(ctx: &Cli, args: &DeleteReleaseArgs) -> Result<(), AppError> {
     let mut mutargs = args.clone();
-    if args.by_id.is_some() && args.by_id.unwrap() == true {
+    if args.by_id == Some(true) {
         let req_url = format!(
             "{}/rest/api/3/version/{}/removeAndSwap",
             ctx.base_jira_url, args.release
@@ -59,20 +59,12 @@ pub fn execute_delete_release(ctx: &Cli, args: &DeleteReleaseArgs) -> Result<(),
 
         debug!("found release {} for name {}", id, args.release);
         mutargs.set_release(id);
-        if args.replace_fix_version.is_some() {
-            let fixid = get_id_from_name(
-                ctx,
-                args.project.clone(),
-                args.replace_fix_version.as_ref().unwrap().clone(),
-            )?;
+        if let Some(fix_version) = &args.replace_fix_version {
+            let fixid = get_id_from_name(ctx, args.project.clone(), fix_version.clone())?;
             mutargs.set_fixversion(fixid);
         }
-        if args.replace_affected_version.is_some() {
-            let affectedid = get_id_from_name(
-                ctx,
-                args.project.clone(),
-                args.replace_affected_version.as_ref().unwrap().clone(),
-            )?;
+        if let Some(affected_version) = &args.replace_affected_version {
+            let affectedid = get_id_from_name(ctx, args.project.clone(), affected_version.clone())?;
             mutargs.set_affectedversion(affectedid);
         }
         util::do_post::<(), HashMap<&str, String>>(
@@ -87,15 +79,12 @@ pub fn execute_delete_release(ctx: &Cli, args: &DeleteReleaseArgs) -> Result<(),
 
 fn assemble_delete_args(args: DeleteReleaseArgs) -> HashMap<&'static str, String> {
     let mut params: HashMap<&str, String> = HashMap::new();
-    if args.replace_affected_version.is_some() {
-        params.insert(
-            "moveAffectedIssuesTo",
-            args.replace_affected_version.unwrap(),
-        );
+    if let Some(affected_version) = args.replace_affected_version {
+        params.insert("moveAffectedIssuesTo", affected_version);
     }
-    if args.replace_fix_version.is_some() {
-        params.insert("moveFixIssuesTo", args.replace_fix_version.unwrap());
+    if let Some(fix_version) = args.replace_fix_version {
+        params.insert("moveFixIssuesTo", fix_version);
     }
 
-    return params;
+    params
 }
@@ -1,10 +1,47 @@
 use super::*;
-use crate::lib::commands::releases::{PaginatedReleases, Release};
+use crate::lib::commands::releases::Release;
 use crate::lib::util;
+use crate::lib::util::{PageBean, PageRequest, Paginator, MAX_ITEMS_HELP};
 use crate::Cli;
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Parser, ValueEnum};
 use std::collections::HashMap;
 
+/// The orderings jira accepts for versions; `-` sorts descending.
+const ORDER_BY: [&str; 15] = [
+    "sequence",
+    "-sequence",
+    "+sequence",
+    "name",
+    "-name",
+    "+name",
+    "description",
+    "-description",
+    "+description",
+    "releaseDate",
+    "-releaseDate",
+    "+releaseDate",
+    "startDate",
+    "-startDate",
+    "+startDate",
+];
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum ReleaseState {
+    Released,
+    Unreleased,
+    Archived,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum ReleaseExpand {
+    /// how many issues are to do, in progress and done
+    #[value(name = "issuesstatus")]
+    IssuesStatus,
+    /// the actions jira offers on the release
+    Operations,
+}
+
 #[derive(Parser, Clone)]
 #[command()]
 pub struct ListReleasesArgs {
@@ -24,20 +61,85 @@ pub struct ListReleasesArgs {
     )]
     pub(crate) unpaginate: bool,
     #[arg(long, default_value_t = 50, help = "how many items to return")]
-    pub(crate) page_size: i64,
+    pub(crate) page_size: u64,
     #[arg(long, default_value_t = 0, help = "item index to begin paging at")]
-    pub(crate) page_start_idx: i64,
+    pub(crate) page_start_idx: u64,
+    #[arg(long, help = MAX_ITEMS_HELP)]
+    pub(crate) max_items: Option<u64>,
+    #[arg(
+        long,
+        value_parser = util::parse_date,
+        help = "only released versions released after this date ex 2023-01-31, -30d, last monday"
+    )]
+    pub(crate) released_after: Option<NaiveDate>,
+    #[arg(
+        long,
+        value_parser = util::parse_date,
+        help = "only unreleased versions due before this date ex +2w, next friday"
+    )]
+    pub(crate) due_before: Option<NaiveDate>,
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "only unreleased versions past their release date"
+    )]
+    pub(crate) overdue: bool,
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "only releases in these states, comma separated"
+    )]
+    pub(crate) status: Vec<ReleaseState>,
+    #[arg(
+        long,
+        allow_hyphen_values = true,
+        value_parser = ORDER_BY,
+        help = "order jira returns the releases in; prefix with - for descending ex -releaseDate"
+    )]
+    pub(crate) order_by: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "extra details to include, comma separated"
+    )]
+    pub(crate) expand: Vec<ReleaseExpand>,
+}
+
+impl Default for ListReleasesArgs {
+    fn default() -> Self {
+        ListReleasesArgs {
+            project: String::new(),
+            filter: None,
+            unpaginate: false,
+            page_size: 50,
+            page_start_idx: 0,
+            max_items: None,
+            released_after: None,
+            due_before: None,
+            overdue: false,
+            status: vec![],
+            order_by: None,
+            expand: vec![],
+        }
+    }
 }
 
 impl ListReleasesArgs {
-    fn update_start_idx(&mut self, new: i64) {
-        self.page_start_idx = new;
+    /// Every release in `project`, unfiltered and fetched a hundred at a time.
+    pub(crate) fn all(project: &str) -> ListReleasesArgs {
+        ListReleasesArgs {
+            project: project.to_string(),
+            unpaginate: true,
+            page_size: 100,
+            ..Default::default()
+        }
     }
 }
 
 pub fn execute_list_releases(ctx: &Cli, args: &ListReleasesArgs) -> Result<(), AppError> {
-    let values = do_list_releases(ctx, args)?;
-    util::format_print::<Release>(values, ctx.output_format)?;
+    util::format_stream(iter_releases(ctx, args), ctx)?;
 
     Ok(())
 }
@@ -46,76 +148,94 @@ pub(crate) fn do_list_releases(
     ctx: &Cli,
     args: &ListReleasesArgs,
 ) -> Result<Vec<Release>, AppError> {
-    let (req_url, query_params) = assemble_query(ctx, args);
-    let url = req_url.clone();
-    let mut values: Vec<Release>;
-    values = vec![];
-    let mut res = util::do_get::<PaginatedReleases<Release>, HashMap<&str, String>>(
-        &req_url,
-        ctx,
-        query_params,
-    )?;
-
-    if args.unpaginate {
-        let arsgs = page_loop(res.total as i64, args);
-        for arg in arsgs {
-            let mut res =
-                util::do_get::<PaginatedReleases<Release>, HashMap<&str, String>>(&url, ctx, arg)?;
-            values.append(&mut res.values);
-        }
-    } else {
-        values.append(&mut res.values);
-    }
-    Ok(values)
+    iter_releases(ctx, args).collect()
 }
 
-fn assemble_query<'a>(ctx: &Cli, args: &'a ListReleasesArgs) -> (String, HashMap<&'a str, String>) {
+/// Yields releases as each page arrives.
+pub(crate) fn iter_releases<'a>(
+    ctx: &'a Cli,
+    args: &ListReleasesArgs,
+) -> impl Iterator<Item = Result<Release, AppError>> + 'a {
     let margs = args.clone();
+    let fargs = args.clone();
+    let filtered = args.released_after.is_some() || args.due_before.is_some() || args.overdue;
+    Paginator::new(
+        args.page_start_idx,
+        args.page_size,
+        args.unpaginate,
+        move |page: &PageRequest| {
+            let (req_url, query_params) = assemble_query(ctx, &margs, page);
+            util::do_get::<PageBean<Release>, HashMap<&str, String>>(&req_url, ctx, query_params)
+        },
+    )
+    // with date filters the limit applies to what is left after filtering
+    .max_items(if filtered { None } else { args.max_items })
+    .filter(move |r| r.as_ref().map_or(true, |r| matches_dates(r, &fargs)))
+    .take(args.max_items.map_or(usize::MAX, |max| max as usize))
+}
+
+/// Whether a release passes the date filters; filtering happens here as jira can't do it.
+fn matches_dates(release: &Release, args: &ListReleasesArgs) -> bool {
+    let released_after = args
+        .released_after
+        .is_none_or(|after| release.released && release.release_date.is_some_and(|d| d > after));
+    let due_before = args
+        .due_before
+        .is_none_or(|before| !release.released && release.release_date.is_some_and(|d| d < before));
+    let overdue = !args.overdue || release.overdue == Some(true);
+    released_after && due_before && overdue
+}
+
+fn assemble_query<'a>(
+    ctx: &Cli,
+    args: &'a ListReleasesArgs,
+    page: &PageRequest,
+) -> (String, HashMap<&'a str, String>) {
     let req_url = format!(
         "{}/rest/api/3/project/{}/version",
         ctx.base_jira_url, args.project
     );
 
-    let query_params = args_to_query_params(margs);
-    (req_url, query_params.clone())
-}
-
-fn args_to_query_params(args: ListReleasesArgs) -> HashMap<&'static str, String> {
     let mut query_params = HashMap::<&str, String>::new();
-    query_params.insert("startAt", args.page_start_idx.to_string());
-    query_params.insert("maxResults", args.page_size.to_string());
-    if args.filter.is_some() {
-        query_params.insert(
-            "query",
-            (*args.filter.as_ref().unwrap().clone()).parse().unwrap(),
-        );
+    query_params.insert("startAt", page.start_at.to_string());
+    query_params.insert("maxResults", page.max_results.to_string());
+    if let Some(filter) = &args.filter {
+        query_params.insert("query", filter.clone());
     }
-    query_params.clone()
-}
-
-fn page_loop(total_results: i64, args: &ListReleasesArgs) -> Vec<HashMap<&str, String>> {
-    let remainder = total_results % args.page_size;
-    let mut pages = total_results / args.page_size;
-    let mut startidx = args.page_start_idx;
-    if remainder > 0 {
-        pages += 1;
+    if !args.status.is_empty() {
+        let status: Vec<&str> = args
+            .status
+            .iter()
+            .map(|s| match s {
+                ReleaseState::Released => "released",
+                ReleaseState::Unreleased => "unreleased",
+                ReleaseState::Archived => "archived",
+            })
+            .collect();
+        query_params.insert("status", status.join(","));
+    }
+    if let Some(order_by) = &args.order_by {
+        query_params.insert("orderBy", order_by.clone());
     }
-    let mut requests: Vec<HashMap<&str, String>>;
-    requests = vec![];
-    for _ in 0..pages {
-        let mut newargs = args.clone();
-        newargs.update_start_idx(startidx);
-        requests.push(args_to_query_params(newargs));
-        startidx = args.page_size + startidx;
+    if !args.expand.is_empty() {
+        let expand: Vec<&str> = args
+            .expand
+            .iter()
+            .map(|e| match e {
+                ReleaseExpand::IssuesStatus => "issuesstatus",
+                ReleaseExpand::Operations => "operations",
+            })
+            .collect();
+        query_params.insert("expand", expand.join(","));
     }
-    return requests;
+    (req_url, query_params)
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::*;
 
-    use crate::lib::util::Format;
+    use crate::lib::util::PageBean;
     use crate::Cli;
     use httptest::{matchers::*, responders::*, Expectation, ServerPool};
 
@@ -125,22 +245,13 @@ mod tests {
     fn list_releases_paginated() -> Result<(), Box<dyn std::error::Error>> {
         let server = SERVER_POOL.get_server();
 
-        let ctx = Cli {
-            auth_token: "".to_string(),
-
-            output_format: Format::Csv,
-            user_email: "".to_string(),
-            base_jira_url: server.url("").to_string(),
-            command: None,
-        };
+        let ctx = Cli::for_tests(server.url("").to_string());
         let args = ListReleasesArgs {
             project: "foo".to_string(),
-            filter: None,
-            unpaginate: false,
             page_size: 1,
-            page_start_idx: 0,
+            ..Default::default()
         };
-        let resp = PaginatedReleases {
+        let resp = PageBean {
             total: 2,
             start_at: 0,
             is_last: true,
@@ -151,10 +262,13 @@ mod tests {
                     name: None,
                     archived: false,
                     released: false,
+                    start_date: None,
                     release_date: None,
                     overdue: None,
                     user_release_date: None,
                     project_id: 0,
+                    issues_status_for_fix_version: None,
+                    operations: None,
                 },
                 Release {
                     id: "2".to_string(),
@@ -162,10 +276,13 @@ mod tests {
                     name: None,
                     archived: false,
                     released: false,
+                    start_date: None,
                     release_date: None,
                     overdue: None,
                     user_release_date: None,
                     project_id: 0,
+                    issues_status_for_fix_version: None,
+                    operations: None,
                 },
             ],
         };
@@ -185,22 +302,14 @@ mod tests {
     fn list_releases_unpaginated() -> Result<(), Box<dyn std::error::Error>> {
         let server = SERVER_POOL.get_server();
 
-        let ctx = Cli {
-            auth_token: "".to_string(),
-
-            output_format: Format::Csv,
-            user_email: "".to_string(),
-            base_jira_url: server.url("").to_string(),
-            command: None,
-        };
+        let ctx = Cli::for_tests(server.url("").to_string());
         let args = ListReleasesArgs {
             project: "foo".to_string(),
-            filter: None,
             unpaginate: true,
             page_size: 1,
-            page_start_idx: 0,
+            ..Default::default()
         };
-        let resp1 = PaginatedReleases {
+        let resp1 = PageBean {
             total: 2,
             start_at: 0,
             is_last: false,
@@ -210,13 +319,16 @@ mod tests {
                 name: None,
                 archived: false,
                 released: false,
+                start_date: None,
                 release_date: None,
                 overdue: None,
                 user_release_date: None,
                 project_id: 0,
+                issues_status_for_fix_version: None,
+                operations: None,
             }],
         };
-        let resp2 = PaginatedReleases {
+        let resp2 = PageBean {
             total: 2,
             start_at: 1,
             is_last: true,
@@ -226,10 +338,13 @@ mod tests {
                 name: None,
                 archived: false,
                 released: false,
+                start_date: None,
                 release_date: None,
                 overdue: None,
                 user_release_date: None,
                 project_id: 0,
+                issues_status_for_fix_version: None,
+                operations: None,
             }],
         };
         // Start a server running on a local ephemeral port.
@@ -237,20 +352,117 @@ mod tests {
         // with a 200 status code.
         server.expect(
             Expectation::matching(request::query(url_decoded(contains(("startAt", "0")))))
-                .times(2)
-                .respond_with(json_encoded(serde_json::json!(resp2))),
+                .respond_with(json_encoded(serde_json::json!(resp1))),
         );
         server.expect(
             Expectation::matching(request::query(url_decoded(contains(("startAt", "1")))))
-                .respond_with(json_encoded(serde_json::json!(resp1))),
+                .respond_with(json_encoded(serde_json::json!(resp2))),
         );
 
-        // server.expect(
-        //     Expectation::matching(request::query(url_decoded(contains(("startAt", any()))))).respond_with(json_encoded(serde_json::json!(resp2))), );
-
         let res = do_list_releases(&ctx, &args)?;
 
         insta::assert_debug_snapshot!(res);
         Ok(())
     }
+
+    #[test]
+    fn list_releases_filtered_by_date() -> Result<(), Box<dyn std::error::Error>> {
+        let server = SERVER_POOL.get_server();
+
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(any()).times(3).respond_with(json_encoded(serde_json::json!({
+                "total": 4,
+                "startAt": 0,
+                "isLast": true,
+                "values": [
+                    {"id": "1", "archived": false, "released": true, "releaseDate": "2023-01-10", "projectId": 0},
+                    {"id": "2", "archived": false, "released": true, "releaseDate": "2023-03-10", "projectId": 0},
+                    {"id": "3", "archived": false, "released": false, "releaseDate": "2023-04-10", "overdue": true, "projectId": 0},
+                    {"id": "4", "archived": false, "released": false, "releaseDate": "2023-09-10", "overdue": false, "projectId": 0}
+                ]
+            }))),
+        );
+        let args = ListReleasesArgs {
+            project: "foo".to_string(),
+            unpaginate: true,
+            released_after: NaiveDate::from_ymd_opt(2023, 2, 1),
+            ..Default::default()
+        };
+        let ids = |releases: Vec<Release>| -> Vec<String> {
+            releases.into_iter().map(|r| r.id).collect()
+        };
+        assert_eq!(ids(do_list_releases(&ctx, &args)?), vec!["2"]);
+
+        let args = ListReleasesArgs {
+            released_after: None,
+            due_before: NaiveDate::from_ymd_opt(2023, 6, 1),
+            ..args
+        };
+        assert_eq!(ids(do_list_releases(&ctx, &args)?), vec!["3"]);
+
+        let args = ListReleasesArgs {
+            overdue: true,
+            max_items: Some(1),
+            ..args
+        };
+        assert_eq!(ids(do_list_releases(&ctx, &args)?), vec!["3"]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_releases_by_status_with_issue_counts() -> Result<(), Box<dyn std::error::Error>> {
+        let server = SERVER_POOL.get_server();
+
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(all_of![
+                request::query(url_decoded(contains(("status", "released,archived")))),
+                request::query(url_decoded(contains(("orderBy", "-releaseDate")))),
+                request::query(url_decoded(contains(("expand", "issuesstatus")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "total": 1,
+                "startAt": 0,
+                "isLast": true,
+                "values": [{
+                    "id": "1", "archived": false, "released": true, "projectId": 0,
+                    "issuesStatusForFixVersion": {"toDo": 1, "inProgress": 2, "done": 3}
+                }]
+            }))),
+        );
+        let args = ListReleasesArgs {
+            project: "foo".to_string(),
+            unpaginate: true,
+            status: vec![ReleaseState::Released, ReleaseState::Archived],
+            order_by: Some("-releaseDate".to_string()),
+            expand: vec![ReleaseExpand::IssuesStatus],
+            ..Default::default()
+        };
+        let res = do_list_releases(&ctx, &args)?;
+        let counts = res[0].issues_status_for_fix_version.as_ref().unwrap();
+        assert_eq!((counts.to_do, counts.in_progress, counts.done), (1, 2, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn release_id_matches_names_exactly() -> Result<(), Box<dyn std::error::Error>> {
+        let server = SERVER_POOL.get_server();
+
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::query(url_decoded(contains(("query", "1.1")))))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "10", "name": "1.10", "archived": false, "released": false, "projectId": 0},
+                        {"id": "1", "name": "1.1", "archived": false, "released": false, "projectId": 0}
+                    ]
+                }))),
+        );
+        assert_eq!(release_id(&ctx, "foo", "1.1", None)?, "1");
+        Ok(())
+    }
 }
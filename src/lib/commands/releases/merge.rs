@@ -0,0 +1,390 @@
+use super::*;
+use chrono::NaiveDate;
+use clap::{ArgGroup, Parser};
+use log::{error, info};
+
+/// The placeholder in a duplicate pattern standing for the name of the release to merge into.
+const NAME_PLACEHOLDER: &str = "{name}";
+
+#[derive(Parser, Clone)]
+#[command(group(ArgGroup::new("merge").required(true).args(["source", "duplicate_pattern"]),))]
+pub struct MergeReleasesArgs {
+    #[arg(
+        long,
+        short,
+        requires = "target",
+        help = "the name (or id) of the release to merge and then delete"
+    )]
+    pub(crate) source: Option<String>,
+    #[arg(
+        long,
+        short,
+        requires = "source",
+        help = "the name (or id) of the release to merge into"
+    )]
+    pub(crate) target: Option<String>,
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(long, short, help = BY_ID_HELP)]
+    pub(crate) by_id: Option<bool>,
+    #[arg(
+        long,
+        conflicts_with = "by_id",
+        help = "merge every release named like this into the release named by its {name} part ex v{name} merges v1.4.0 into 1.4.0"
+    )]
+    pub(crate) duplicate_pattern: Option<String>,
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "show what would be merged without changing anything"
+    )]
+    pub(crate) dry_run: bool,
+}
+
+/// One source release folded into its target, or that would be with --dry-run.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    source: String,
+    target: String,
+    fix_issues_moved: u64,
+    affected_issues_moved: u64,
+    description: Option<String>,
+    start_date: Option<NaiveDate>,
+    release_date: Option<NaiveDate>,
+    merged: bool,
+}
+
+pub fn execute_merge_releases(ctx: &Cli, args: &MergeReleasesArgs) -> Result<(), AppError> {
+    let report = do_merge_releases(ctx, args)?;
+    util::format_print(report, ctx)
+}
+
+pub fn do_merge_releases(
+    ctx: &Cli,
+    args: &MergeReleasesArgs,
+) -> Result<Vec<MergeReport>, AppError> {
+    let mut report = vec![];
+    for (source, target) in merge_pairs(ctx, args)? {
+        match merge(ctx, args, source, target) {
+            Ok(merged) => report.push(merged),
+            Err(e) => {
+                // earlier pairs are already merged, so say which before giving up
+                if !report.is_empty() {
+                    error!("stopped after merging {} release(s):", report.len());
+                    util::format_print(report, ctx)?;
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// The (source, target) releases to merge, either the two named or every duplicate pair.
+fn merge_pairs(ctx: &Cli, args: &MergeReleasesArgs) -> Result<Vec<(Release, Release)>, AppError> {
+    if let (Some(source), Some(target)) = (&args.source, &args.target) {
+        let (source, target) = (lookup(ctx, args, source)?, lookup(ctx, args, target)?);
+        if source.id == target.id {
+            return Err(AppError::MergeIntoItself(source.name.unwrap_or(source.id)));
+        }
+        return Ok(vec![(source, target)]);
+    }
+    let pattern = args.duplicate_pattern.clone().unwrap_or_default();
+    let (prefix, suffix) = pattern
+        .split_once(NAME_PLACEHOLDER)
+        .filter(|(prefix, suffix)| !(prefix.is_empty() && suffix.is_empty()))
+        .ok_or_else(|| {
+            AppError::InvalidReleaseName(format!(
+                "duplicate pattern '{}' needs {} and some text around it",
+                pattern, NAME_PLACEHOLDER
+            ))
+        })?;
+
+    let mut releases = do_list_releases(ctx, &ListReleasesArgs::all(&args.project))?;
+    let mut pairs = vec![];
+    while let Some(idx) = releases
+        .iter()
+        .position(|r| duplicate_of(r, prefix, suffix).is_some())
+    {
+        let source = releases.remove(idx);
+        let name = duplicate_of(&source, prefix, suffix).unwrap_or_default();
+        match releases
+            .iter()
+            .position(|r| r.name.as_deref() == Some(name.as_str()))
+        {
+            Some(target) => pairs.push((source, releases.remove(target))),
+            None => debug!("nothing named {} to merge {:?} into", name, source.name),
+        }
+    }
+    Ok(pairs)
+}
+
+fn duplicate_of(release: &Release, prefix: &str, suffix: &str) -> Option<String> {
+    let name = release.name.as_deref()?;
+    name.strip_prefix(prefix)?
+        .strip_suffix(suffix)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+}
+
+fn lookup(ctx: &Cli, args: &MergeReleasesArgs, release: &str) -> Result<Release, AppError> {
+    if args.by_id == Some(true) {
+        do_get_release(ctx, release)
+    } else {
+        find_release_by_name(ctx, args.project.clone(), release.to_string())?
+            .ok_or_else(|| AppError::ReleaseNotFound(release.to_string()))
+    }
+}
+
+/// Gives the target the combined description, the earlier start date and the later release
+/// date, then deletes the source, moving its fix and affects versions over to the target.
+fn merge(
+    ctx: &Cli,
+    args: &MergeReleasesArgs,
+    source: Release,
+    target: Release,
+) -> Result<MergeReport, AppError> {
+    let counts = do_related_issue_counts(ctx, &source.id)?;
+    let description = match (&target.description, &source.description) {
+        (Some(t), Some(s)) if !s.is_empty() && !t.contains(s.as_str()) => {
+            Some(format!("{}\n{}", t, s))
+        }
+        (None, Some(s)) => Some(s.clone()),
+        (t, _) => t.clone(),
+    };
+    let start_date = earliest(&target.start_date, &source.start_date);
+    let release_date = latest(&target.release_date, &source.release_date);
+    let report = MergeReport {
+        source: source.name.clone().unwrap_or(source.id.clone()),
+        target: target.name.clone().unwrap_or(target.id.clone()),
+        fix_issues_moved: counts.issues_fixed_count,
+        affected_issues_moved: counts.issues_affected_count,
+        description: description.clone(),
+        start_date,
+        release_date,
+        merged: !args.dry_run,
+    };
+    if args.dry_run {
+        return Ok(report);
+    }
+
+    // only send what changed, so an unchanged target isn't touched
+    let update = UpdateReleaseArgs {
+        name: None,
+        description: changed(&description, &target.description),
+        start_date: changed(&start_date, &target.start_date),
+        release_date: changed(&release_date, &target.release_date),
+        is_released: None,
+        archived: None,
+        by_id: Some(true),
+        release: target.id.clone(),
+        project: args.project.clone(),
+    };
+    if update.description.is_some() || update.start_date.is_some() || update.release_date.is_some()
+    {
+        do_update_release(ctx, &update)?;
+    }
+    execute_delete_release(
+        ctx,
+        &DeleteReleaseArgs {
+            release: source.id.clone(),
+            project: args.project.clone(),
+            by_id: Some(true),
+            replace_fix_version: Some(target.id.clone()),
+            replace_affected_version: Some(target.id.clone()),
+        },
+    )?;
+    info!("merged release {} into {}", report.source, report.target);
+    Ok(report)
+}
+
+fn earliest<T: Ord + Clone>(a: &Option<T>, b: &Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b).clone()),
+        _ => a.clone().or(b.clone()),
+    }
+}
+
+fn latest<T: Ord + Clone>(a: &Option<T>, b: &Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b).clone()),
+        _ => a.clone().or(b.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn releases() -> serde_json::Value {
+        serde_json::json!({
+            "total": 3,
+            "startAt": 0,
+            "isLast": true,
+            "values": [
+                {"id": "1", "name": "1.4.0", "description": "api", "archived": false, "released": false,
+                 "startDate": "2023-01-10", "releaseDate": "2023-02-01", "projectId": 1},
+                {"id": "2", "name": "v1.4.0", "description": "ui", "archived": false, "released": false,
+                 "startDate": "2023-01-05", "releaseDate": "2023-01-20", "projectId": 1},
+                {"id": "3", "name": "v1.5.0", "archived": false, "released": false, "projectId": 1}
+            ]
+        })
+    }
+
+    fn args(dry_run: bool) -> MergeReleasesArgs {
+        MergeReleasesArgs {
+            source: None,
+            target: None,
+            project: "FOO".to_string(),
+            by_id: None,
+            duplicate_pattern: Some("v{name}".to_string()),
+            dry_run,
+        }
+    }
+
+    fn expect_listing(server: &httptest::Server) {
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/FOO/version",
+            ))
+            .respond_with(json_encoded(releases())),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/version/2/relatedIssueCounts",
+            ))
+            .respond_with(json_encoded(serde_json::json!({
+                "issuesFixedCount": 4,
+                "issuesAffectedCount": 1
+            }))),
+        );
+    }
+
+    #[test]
+    fn dry_run_changes_nothing() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        expect_listing(&server);
+
+        let report = do_merge_releases(&ctx, &args(true)).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(
+            (report[0].source.as_str(), report[0].target.as_str()),
+            ("v1.4.0", "1.4.0")
+        );
+        assert_eq!(report[0].fix_issues_moved, 4);
+        assert!(!report[0].merged);
+    }
+
+    #[test]
+    fn merges_duplicates_into_their_target() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        expect_listing(&server);
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/version/1"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "description": "api\nui",
+                    "startDate": "2023-01-05"
+                })))),
+            ])
+            .respond_with(status_code(200)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/version/2/removeAndSwap"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "moveFixIssuesTo": "1",
+                    "moveAffectedIssuesTo": "1"
+                })))),
+            ])
+            .respond_with(status_code(204)),
+        );
+
+        let report = do_merge_releases(&ctx, &args(false)).unwrap();
+        assert_eq!(report[0].release_date, NaiveDate::from_ymd_opt(2023, 2, 1));
+        assert!(report[0].merged);
+    }
+
+    #[test]
+    fn refuses_to_merge_a_release_into_itself() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/version/1"))
+                .times(2)
+                .respond_with(json_encoded(serde_json::json!({
+                    "id": "1", "name": "1.4.0", "archived": false, "released": false, "projectId": 1
+                }))),
+        );
+        let args = MergeReleasesArgs {
+            source: Some("1".to_string()),
+            target: Some("1".to_string()),
+            by_id: Some(true),
+            duplicate_pattern: None,
+            ..args(false)
+        };
+
+        assert!(matches!(
+            do_merge_releases(&ctx, &args),
+            Err(AppError::MergeIntoItself(name)) if name == "1.4.0"
+        ));
+    }
+
+    #[test]
+    fn stops_at_the_first_failed_merge() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/FOO/version",
+            ))
+            .respond_with(json_encoded(serde_json::json!({
+                "total": 4,
+                "startAt": 0,
+                "isLast": true,
+                "values": [
+                    {"id": "1", "name": "1.4.0", "archived": false, "released": false, "projectId": 1},
+                    {"id": "2", "name": "v1.4.0", "archived": false, "released": false, "projectId": 1},
+                    {"id": "3", "name": "1.5.0", "archived": false, "released": false, "projectId": 1},
+                    {"id": "4", "name": "v1.5.0", "archived": false, "released": false, "projectId": 1}
+                ]
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                matches("^/rest/api/3/version/[24]/relatedIssueCounts$"),
+            ))
+            .times(2)
+            .respond_with(json_encoded(serde_json::json!({
+                "issuesFixedCount": 0,
+                "issuesAffectedCount": 0
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "POST",
+                "/rest/api/3/version/2/removeAndSwap",
+            ))
+            .respond_with(status_code(204)),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "POST",
+                "/rest/api/3/version/4/removeAndSwap",
+            ))
+            .respond_with(status_code(500)),
+        );
+
+        assert!(do_merge_releases(&ctx, &args(false)).is_err());
+    }
+}
@@ -1,5 +1,4 @@
-use clap::arg;
-
+use chrono::NaiveDate;
 use log::debug;
 use serde::Deserialize;
 use serde::Serialize;
@@ -7,24 +6,37 @@ use serde::Serialize;
 pub use create::*;
 pub use delete::*;
 pub use list::*;
+pub use merge::*;
+pub use move_release::*;
+pub use next_name::*;
+pub use prune::*;
+pub use sync::*;
 pub use update::*;
 
-use crate::lib::AppError;
+use crate::lib::{util, AppError};
 use crate::Cli;
+use std::collections::HashMap;
 
 mod create;
 mod delete;
 mod list;
+mod merge;
+mod move_release;
+mod next_name;
+mod prune;
+mod sync;
 mod update;
 
 pub const PROJECT_HELP: &str = "project identifier";
 pub const NAME_HELP: &str = "name of the release";
 pub const DESCRIPTION_HELP: &str = "description of the release";
-const START_DATE_HELP: &str = "start date of the version in ISO 8601 format (yyyy-mm-dd)";
-pub const RELEASE_DATE_HELP: &str = "release date of the version in ISO 8601 format (yyyy-mm-dd)";
+const START_DATE_HELP: &str =
+    "start date of the version, yyyy-mm-dd or relative ex today, +2w, next friday";
+pub const RELEASE_DATE_HELP: &str =
+    "release date of the version, yyyy-mm-dd or relative ex today, +2w, next friday";
 pub const BY_ID_HELP: &str =
     "perform operation by specifying id rather than name (useful if your names are not unique)";
-const RELEASE_HELP: &str = "the name or id of the release to perform the operation upon";
+pub const RELEASE_HELP: &str = "the name or id of the release to perform the operation upon";
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -34,34 +46,118 @@ pub struct Release {
     pub(crate) name: Option<String>,
     pub(crate) archived: bool,
     pub(crate) released: bool,
-    pub(crate) release_date: Option<String>,
+    pub(crate) start_date: Option<NaiveDate>,
+    pub(crate) release_date: Option<NaiveDate>,
     pub(crate) overdue: Option<bool>,
     pub(crate) user_release_date: Option<String>,
     pub(crate) project_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) issues_status_for_fix_version: Option<IssueStatusCounts>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) operations: Option<serde_json::Value>,
+}
+
+/// How many of a release's issues are in each status category, from `--expand issuesstatus`.
+/// Jira leaves out categories with no issues.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IssueStatusCounts {
+    pub(crate) unmapped: u64,
+    pub(crate) to_do: u64,
+    pub(crate) in_progress: u64,
+    pub(crate) done: u64,
+}
+
+/// `new`, if it is set and differs from `old`; for sending only the fields that changed.
+pub(crate) fn changed<T: PartialEq + Clone>(new: &Option<T>, old: &Option<T>) -> Option<T> {
+    new.as_ref().filter(|n| Some(*n) != old.as_ref()).cloned()
+}
+
+/// How many issues have a release as their fix version or affects version.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedIssueCounts {
+    pub(crate) issues_fixed_count: u64,
+    pub(crate) issues_affected_count: u64,
 }
 
+pub(crate) fn do_related_issue_counts(
+    ctx: &Cli,
+    id: &str,
+) -> Result<RelatedIssueCounts, AppError> {
+    let req_url = format!(
+        "{}/rest/api/3/version/{}/relatedIssueCounts",
+        ctx.base_jira_url, id
+    );
+    util::do_get::<RelatedIssueCounts, HashMap<&str, String>>(&req_url, ctx, HashMap::new())
+}
+
+/// How many of a release's issues are still unresolved, out of all of them.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct PaginatedReleases<T> {
-    pub(crate) total: u64,
-    pub(crate) start_at: u64,
-    pub(crate) is_last: bool,
-    pub(crate) values: Vec<T>,
+pub struct UnresolvedIssueCount {
+    pub(crate) issues_unresolved_count: u64,
+    pub(crate) issues_count: u64,
 }
 
+pub(crate) fn do_unresolved_issue_count(
+    ctx: &Cli,
+    id: &str,
+) -> Result<UnresolvedIssueCount, AppError> {
+    let req_url = format!(
+        "{}/rest/api/3/version/{}/unresolvedIssueCount",
+        ctx.base_jira_url, id
+    );
+    util::do_get::<UnresolvedIssueCount, HashMap<&str, String>>(&req_url, ctx, HashMap::new())
+}
+
+/// Fetches a single release by id.
+pub(crate) fn do_get_release(ctx: &Cli, id: &str) -> Result<Release, AppError> {
+    let req_url = format!("{}/rest/api/3/version/{}", ctx.base_jira_url, id);
+    util::do_get::<Release, HashMap<&str, String>>(&req_url, ctx, HashMap::new())
+}
+
+/// The id of `release`, looking it up by name unless `by_id` says it already is one.
+pub(crate) fn release_id(
+    ctx: &Cli,
+    project: &str,
+    release: &str,
+    by_id: Option<bool>,
+) -> Result<String, AppError> {
+    if by_id == Some(true) {
+        Ok(release.to_string())
+    } else {
+        let id = get_id_from_name(ctx, project.to_string(), release.to_string())?;
+        debug!("found release {} for name {}", id, release);
+        Ok(id)
+    }
+}
+
+/// The id of the release named exactly `name`; jira's name query also matches longer names
+/// containing it, so 1.1 would otherwise find 1.10 too.
 fn get_id_from_name(ctx: &Cli, project: String, name: String) -> Result<String, AppError> {
+    find_release_by_name(ctx, project, name.clone())?
+        .map(|r| r.id)
+        .ok_or(AppError::ReleaseNotFound(name))
+}
+
+/// Finds the release in a project whose name is exactly `name`, if there is one.
+pub(crate) fn find_release_by_name(
+    ctx: &Cli,
+    project: String,
+    name: String,
+) -> Result<Option<Release>, AppError> {
     let args = ListReleasesArgs {
-        project,
-        filter: Option::from(name),
-        unpaginate: true,
-        page_size: 100,
-        page_start_idx: 0,
+        filter: Option::from(name.clone()),
+        ..ListReleasesArgs::all(&project)
     };
-    let result = do_list_releases(ctx, &args)?;
-    if result.len() != 1 {
+    let mut matched: Vec<Release> = do_list_releases(ctx, &args)?
+        .into_iter()
+        .filter(|r| r.name.as_deref() == Some(name.as_str()))
+        .collect();
+    if matched.len() > 1 {
         Err(AppError::MatchedMultipleReleases)
     } else {
-        let id = result.get(0).unwrap().id.clone();
-        Ok(id)
+        Ok(matched.pop())
     }
 }
@@ -0,0 +1,244 @@
+use super::*;
+use clap::{ArgGroup, Parser, ValueEnum};
+use regex::Regex;
+use semver::Version;
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum Position {
+    First,
+    Last,
+    Earlier,
+    Later,
+}
+
+impl Position {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Position::First => "First",
+            Position::Last => "Last",
+            Position::Earlier => "Earlier",
+            Position::Later => "Later",
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq)]
+pub enum SortKey {
+    /// by the semantic version in the name; names without one go last
+    Semver,
+    /// by release date; undated releases go last
+    ReleaseDate,
+}
+
+#[derive(Parser, Clone)]
+#[command(group(ArgGroup::new("to").required(true).args(["after", "position", "auto_sort"]),))]
+pub struct MoveReleaseArgs {
+    #[arg(long, short, required_unless_present = "auto_sort", help = RELEASE_HELP)]
+    pub(crate) release: Option<String>,
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(long, short, help = BY_ID_HELP)]
+    pub(crate) by_id: Option<bool>,
+    #[arg(
+        long,
+        short,
+        help = "the name (or id) of the release to place it after"
+    )]
+    pub(crate) after: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        help = "where to place it instead of after another release"
+    )]
+    pub(crate) position: Option<Position>,
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "release",
+        help = "reorder all of the project's releases"
+    )]
+    pub(crate) auto_sort: Option<SortKey>,
+}
+
+/// One move made while sorting.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveReport {
+    release: String,
+    placed: String,
+}
+
+pub fn execute_move_release(ctx: &Cli, args: &MoveReleaseArgs) -> Result<(), AppError> {
+    match (&args.release, args.auto_sort) {
+        (Some(release), _) => {
+            let id = release_id(ctx, &args.project, release, args.by_id)?;
+            let after = match &args.after {
+                Some(after) => Some(release_id(ctx, &args.project, after, args.by_id)?),
+                None => None,
+            };
+            if let Some(moved) = do_move_release(ctx, &id, after.as_deref(), args.position)? {
+                util::format_print(vec![moved], ctx)?;
+            }
+            Ok(())
+        }
+        (None, Some(key)) => util::format_print(do_sort_releases(ctx, &args.project, key)?, ctx),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Moves a release after another one, given by id, or to a position.
+pub fn do_move_release(
+    ctx: &Cli,
+    id: &str,
+    after: Option<&str>,
+    position: Option<Position>,
+) -> Result<Option<Release>, AppError> {
+    let req_url = format!("{}/rest/api/3/version/{}/move", ctx.base_jira_url, id);
+    let mut body: HashMap<&str, String> = HashMap::new();
+    if let Some(after) = after {
+        // jira wants the other release's url rather than its id
+        body.insert(
+            "after",
+            format!("{}/rest/api/3/version/{}", ctx.base_jira_url, after),
+        );
+    } else if let Some(position) = position {
+        body.insert("position", position.as_str().to_string());
+    }
+    util::do_post::<Release, HashMap<&str, String>>(&req_url, ctx, &body)
+}
+
+/// Reorders every release in the project, moving only the ones out of place.
+pub fn do_sort_releases(
+    ctx: &Cli,
+    project: &str,
+    key: SortKey,
+) -> Result<Vec<MoveReport>, AppError> {
+    let mut current = do_list_releases(ctx, &ListReleasesArgs::all(project))?;
+    let desired: Vec<String> = sorted(&current, key)
+        .into_iter()
+        .map(|r| r.id.clone())
+        .collect();
+
+    let mut report = vec![];
+    for (idx, id) in desired.iter().enumerate() {
+        if current[idx].id == *id {
+            continue;
+        }
+        let from = current.iter().position(|r| r.id == *id).unwrap_or(idx);
+        let release = current.remove(from);
+        let placed = if idx == 0 {
+            do_move_release(ctx, id, None, Some(Position::First))?;
+            "first".to_string()
+        } else {
+            let previous = &current[idx - 1];
+            do_move_release(ctx, id, Some(&previous.id), None)?;
+            format!(
+                "after {}",
+                previous.name.clone().unwrap_or(previous.id.clone())
+            )
+        };
+        report.push(MoveReport {
+            release: release.name.clone().unwrap_or(release.id.clone()),
+            placed,
+        });
+        current.insert(idx, release);
+    }
+    Ok(report)
+}
+
+/// The releases in their sorted order; ties and unsortable releases keep their current order.
+fn sorted(releases: &[Release], key: SortKey) -> Vec<&Release> {
+    let mut sorted: Vec<&Release> = releases.iter().collect();
+    match key {
+        SortKey::Semver => {
+            static VERSION: OnceLock<Regex> = OnceLock::new();
+            let version = VERSION.get_or_init(|| {
+                Regex::new(r"\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?").unwrap()
+            });
+            let key = |r: &Release| {
+                r.name
+                    .as_deref()
+                    .and_then(|n| version.find(n))
+                    .and_then(|m| Version::parse(m.as_str()).ok())
+            };
+            sorted.sort_by(|a, b| present_first(key(a), key(b)));
+        }
+        SortKey::ReleaseDate => {
+            sorted.sort_by(|a, b| present_first(a.release_date, b.release_date));
+        }
+    }
+    sorted
+}
+
+fn present_first<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn moved(id: &str) -> serde_json::Value {
+        serde_json::json!({"id": id, "archived": false, "released": false, "projectId": 1})
+    }
+
+    #[test]
+    fn sorts_by_semver_moving_only_what_is_out_of_place() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 4,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "1", "name": "v1.10.0", "archived": false, "released": false, "projectId": 1},
+                        {"id": "2", "name": "v1.2.0", "archived": false, "released": false, "projectId": 1},
+                        {"id": "3", "name": "backlog", "archived": false, "released": false, "projectId": 1},
+                        {"id": "4", "name": "v1.9.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/version/2/move"),
+                request::body(json_decoded(eq(serde_json::json!({"position": "First"})))),
+            ])
+            .respond_with(json_encoded(moved("2"))),
+        );
+        let after_2 = format!("{}/rest/api/3/version/2", ctx.base_jira_url);
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/version/4/move"),
+                request::body(json_decoded(eq(serde_json::json!({"after": after_2})))),
+            ])
+            .respond_with(json_encoded(moved("4"))),
+        );
+
+        let report = do_sort_releases(&ctx, "FOO", SortKey::Semver).unwrap();
+        assert_eq!(
+            report,
+            vec![
+                MoveReport {
+                    release: "v1.2.0".to_string(),
+                    placed: "first".to_string()
+                },
+                MoveReport {
+                    release: "v1.9.0".to_string(),
+                    placed: "after v1.2.0".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use super::*;
+use clap::{Parser, ValueEnum};
+use semver::{BuildMetadata, Prerelease, Version};
+
+/// The placeholder in a name pattern that the version is substituted into.
+const SEMVER_PLACEHOLDER: &str = "{semver}";
+
+/// Which part of the version to increment; ordered so the largest bump wins.
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Parser, Clone, Debug)]
+#[command()]
+pub struct ReleaseNameArgs {
+    #[arg(
+        long,
+        default_value = SEMVER_PLACEHOLDER,
+        help = "how release names are built from a semantic version ex app-v{semver}"
+    )]
+    pub name_pattern: String,
+
+    #[arg(
+        long,
+        help = "make the new version a numbered pre-release ex rc gives 1.3.0-rc.1, then 1.3.0-rc.2"
+    )]
+    pub pre_release: Option<String>,
+
+    #[arg(
+        long,
+        help = "build metadata to append to the new version ex 20230131 gives 1.3.0+20230131"
+    )]
+    pub build: Option<String>,
+}
+
+impl Default for ReleaseNameArgs {
+    fn default() -> Self {
+        ReleaseNameArgs {
+            name_pattern: SEMVER_PLACEHOLDER.to_string(),
+            pre_release: None,
+            build: None,
+        }
+    }
+}
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct NextReleaseNameArgs {
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "which part of the highest existing version to increment"
+    )]
+    pub(crate) bump: Bump,
+
+    #[command(flatten)]
+    pub(crate) naming: ReleaseNameArgs,
+}
+
+pub fn execute_next_release_name(ctx: &Cli, args: &NextReleaseNameArgs) -> Result<(), AppError> {
+    println!(
+        "{}",
+        do_release_name_to_make(ctx, &args.project, args.bump, &args.naming)?
+    );
+    Ok(())
+}
+
+/// Works out the name of the release after the highest one in the project matching the
+/// name pattern. Releases whose names don't match the pattern are ignored.
+pub fn do_next_release_name(
+    ctx: &Cli,
+    project: &str,
+    bump: Bump,
+    naming: &ReleaseNameArgs,
+) -> Result<String, AppError> {
+    let pattern = NamePattern::parse(&naming.name_pattern)?;
+    let existing: Vec<Version> = matching_versions(ctx, project, &pattern)?
+        .into_iter()
+        .map(|(version, _)| version)
+        .collect();
+    let next = next_version(
+        &existing,
+        bump,
+        naming.pre_release.as_deref(),
+        naming.build.as_deref(),
+    )?;
+    debug!(
+        "{0} existing versions match {1}, next is {2}",
+        existing.len(),
+        naming.name_pattern,
+        next
+    );
+    Ok(pattern.name_for(&next))
+}
+
+/// The name a release bumped by `bump` gets, as printed by `next-release-name` and used by the
+/// release commands. Like `do_next_release_name`, except that if bumping the highest released
+/// version names a release that exists and is still unreleased, that release was created ahead
+/// of time or by a run that was cut short, and its name is given again.
+pub fn do_release_name_to_make(
+    ctx: &Cli,
+    project: &str,
+    bump: Bump,
+    naming: &ReleaseNameArgs,
+) -> Result<String, AppError> {
+    let pattern = NamePattern::parse(&naming.name_pattern)?;
+    let existing = matching_versions(ctx, project, &pattern)?;
+    let released: Vec<Version> = existing
+        .iter()
+        .filter(|(_, released)| *released)
+        .map(|(version, _)| version.clone())
+        .collect();
+    let pre_release = naming.pre_release.as_deref();
+    let build = naming.build.as_deref();
+    let unfinished = next_version(&released, bump, pre_release, build)?;
+    if existing
+        .iter()
+        .any(|(version, released)| !released && *version == unfinished)
+    {
+        debug!("continuing with unreleased {0}", unfinished);
+        return Ok(pattern.name_for(&unfinished));
+    }
+    let all: Vec<Version> = existing.into_iter().map(|(version, _)| version).collect();
+    Ok(pattern.name_for(&next_version(&all, bump, pre_release, build)?))
+}
+
+/// The versions of the project's releases whose names match `pattern`, each with whether it
+/// has been released.
+fn matching_versions(
+    ctx: &Cli,
+    project: &str,
+    pattern: &NamePattern,
+) -> Result<Vec<(Version, bool)>, AppError> {
+    let args = ListReleasesArgs::all(project);
+    Ok(do_list_releases(ctx, &args)?
+        .iter()
+        .filter_map(|r| {
+            let version = r.name.as_deref().and_then(|n| pattern.version_of(n))?;
+            Some((version, r.released))
+        })
+        .collect())
+}
+
+/// A release name template such as `app-v{semver}`, split around the placeholder.
+pub(crate) struct NamePattern {
+    prefix: String,
+    suffix: String,
+}
+
+impl NamePattern {
+    pub(crate) fn parse(pattern: &str) -> Result<NamePattern, AppError> {
+        match pattern.split_once(SEMVER_PLACEHOLDER) {
+            Some((prefix, suffix)) if !suffix.contains(SEMVER_PLACEHOLDER) => Ok(NamePattern {
+                prefix: prefix.to_string(),
+                suffix: suffix.to_string(),
+            }),
+            _ => Err(AppError::InvalidReleaseName(format!(
+                "name pattern '{}' must contain {} exactly once",
+                pattern, SEMVER_PLACEHOLDER
+            ))),
+        }
+    }
+
+    pub(crate) fn version_of(&self, name: &str) -> Option<Version> {
+        let version = name
+            .strip_prefix(&self.prefix)?
+            .strip_suffix(&self.suffix)?;
+        Version::parse(version).ok()
+    }
+
+    pub(crate) fn name_for(&self, version: &Version) -> String {
+        format!("{}{}{}", self.prefix, version, self.suffix)
+    }
+}
+
+/// The version after the highest of `existing`, or after 0.0.0 if there are none.
+///
+/// Bumping a pre-release finishes it rather than skipping past it, so 1.3.0-rc.2 bumped by
+/// minor is 1.3.0. A pre-release label is numbered after any existing pre-releases of the
+/// same version with that label.
+pub(crate) fn next_version(
+    existing: &[Version],
+    bump: Bump,
+    pre_release: Option<&str>,
+    build: Option<&str>,
+) -> Result<Version, AppError> {
+    let base = existing
+        .iter()
+        .max()
+        .cloned()
+        .unwrap_or(Version::new(0, 0, 0));
+    let mut next = bumped(&base, bump);
+
+    if let Some(label) = pre_release {
+        let prefix = format!("{}.", label);
+        let last = existing
+            .iter()
+            .filter(|v| (v.major, v.minor, v.patch) == (next.major, next.minor, next.patch))
+            .filter_map(|v| v.pre.as_str().strip_prefix(&prefix)?.parse::<u64>().ok())
+            .max()
+            .unwrap_or(0);
+        next.pre = Prerelease::new(&format!("{}{}", prefix, last + 1))
+            .map_err(|e| AppError::InvalidReleaseName(format!("pre-release {}: {}", label, e)))?;
+    }
+    if let Some(build) = build {
+        next.build = BuildMetadata::new(build)
+            .map_err(|e| AppError::InvalidReleaseName(format!("build {}: {}", build, e)))?;
+    }
+    Ok(next)
+}
+
+fn bumped(version: &Version, bump: Bump) -> Version {
+    let v = version;
+    let pre = !v.pre.is_empty();
+    match bump {
+        Bump::Major if pre && v.minor == 0 && v.patch == 0 => Version::new(v.major, 0, 0),
+        Bump::Major => Version::new(v.major + 1, 0, 0),
+        Bump::Minor if pre && v.patch == 0 => Version::new(v.major, v.minor, 0),
+        Bump::Minor => Version::new(v.major, v.minor + 1, 0),
+        Bump::Patch if pre => Version::new(v.major, v.minor, v.patch),
+        Bump::Patch => Version::new(v.major, v.minor, v.patch + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn versions(list: &[&str]) -> Vec<Version> {
+        list.iter().map(|v| Version::parse(v).unwrap()).collect()
+    }
+
+    fn next(existing: &[&str], bump: Bump, pre: Option<&str>) -> String {
+        next_version(&versions(existing), bump, pre, None)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn bumps_the_highest_version() {
+        let existing = ["1.2.3", "1.10.0", "1.9.9"];
+        assert_eq!(next(&existing, Bump::Patch, None), "1.10.1");
+        assert_eq!(next(&existing, Bump::Minor, None), "1.11.0");
+        assert_eq!(next(&existing, Bump::Major, None), "2.0.0");
+        assert_eq!(next(&[], Bump::Minor, None), "0.1.0");
+    }
+
+    #[test]
+    fn pre_releases_are_numbered_then_finished() {
+        assert_eq!(next(&["1.2.3"], Bump::Minor, Some("rc")), "1.3.0-rc.1");
+        assert_eq!(
+            next(&["1.2.3", "1.3.0-rc.1"], Bump::Minor, Some("rc")),
+            "1.3.0-rc.2"
+        );
+        assert_eq!(next(&["1.2.3", "1.3.0-rc.2"], Bump::Minor, None), "1.3.0");
+        assert_eq!(next(&["1.3.0-rc.2"], Bump::Major, None), "2.0.0");
+    }
+
+    #[test]
+    fn build_metadata_is_appended() {
+        let version = next_version(&versions(&["1.0.0"]), Bump::Patch, None, Some("b.7")).unwrap();
+        assert_eq!(version.to_string(), "1.0.1+b.7");
+    }
+
+    #[test]
+    fn name_patterns() {
+        let pattern = NamePattern::parse("app-v{semver}").unwrap();
+        assert_eq!(
+            pattern.version_of("app-v1.2.3"),
+            Some(Version::new(1, 2, 3))
+        );
+        assert_eq!(pattern.version_of("other-v1.2.3"), None);
+        assert_eq!(pattern.version_of("app-vnext"), None);
+        assert_eq!(pattern.name_for(&Version::new(2, 0, 0)), "app-v2.0.0");
+        assert!(NamePattern::parse("app-v").is_err());
+    }
+
+    #[test]
+    fn next_name_ignores_other_patterns() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 3,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "1", "name": "app-v1.4.0", "archived": false, "released": true, "projectId": 1},
+                        {"id": "2", "name": "lib-v3.0.0", "archived": false, "released": true, "projectId": 1},
+                        {"id": "3", "name": "app-v1.4.1", "archived": false, "released": true, "projectId": 1}
+                    ]
+                }))),
+        );
+
+        let naming = ReleaseNameArgs {
+            name_pattern: "app-v{semver}".to_string(),
+            ..Default::default()
+        };
+        let name = do_next_release_name(&ctx, "FOO", Bump::Minor, &naming).unwrap();
+        assert_eq!(name, "app-v1.5.0");
+    }
+
+    #[test]
+    fn name_to_make_reuses_an_unreleased_next_version() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .times(2)
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "1", "name": "1.2.0", "archived": false, "released": true, "projectId": 1},
+                        {"id": "2", "name": "1.3.0", "archived": false, "released": false, "projectId": 1}
+                    ]
+                }))),
+        );
+
+        let naming = ReleaseNameArgs::default();
+        let name = |bump| do_release_name_to_make(&ctx, "FOO", bump, &naming).unwrap();
+        assert_eq!(name(Bump::Minor), "1.3.0");
+        assert_eq!(name(Bump::Major), "2.0.0");
+    }
+}
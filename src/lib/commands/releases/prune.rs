@@ -0,0 +1,292 @@
+use super::*;
+use chrono::{Days, NaiveDate, Utc};
+use clap::{ArgGroup, Parser};
+use log::info;
+use regex::Regex;
+
+#[derive(Parser, Clone)]
+#[command(group(ArgGroup::new("age").required(true).multiple(true).args(["older_than_days", "keep_newest"]),))]
+pub struct PruneReleasesArgs {
+    #[arg(long, short, help = PROJECT_HELP)]
+    pub(crate) project: String,
+    #[arg(
+        long,
+        help = "only prune releases whose release date is more than this many days ago"
+    )]
+    pub(crate) older_than_days: Option<u64>,
+    #[arg(
+        long,
+        help = "only prune releases beyond the newest this many, by release date"
+    )]
+    pub(crate) keep_newest: Option<usize>,
+    #[arg(
+        long,
+        help = "only prune releases whose name matches this regex ex ^app-v"
+    )]
+    pub(crate) name_filter: Option<String>,
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "delete the releases instead of archiving them"
+    )]
+    pub(crate) delete: bool,
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "show what would be pruned without changing anything"
+    )]
+    pub(crate) dry_run: bool,
+}
+
+/// What happened, or would happen, to one release.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    release: String,
+    id: String,
+    release_date: Option<NaiveDate>,
+    action: &'static str,
+    reason: Option<String>,
+}
+
+pub fn execute_prune_releases(ctx: &Cli, args: &PruneReleasesArgs) -> Result<(), AppError> {
+    let report = do_prune_releases(ctx, args, Utc::now().date_naive())?;
+    util::format_print(report, ctx)
+}
+
+/// Archives or deletes released versions matching the age and name filters. Versions that still
+/// have unresolved issues are reported as skipped and left alone.
+pub fn do_prune_releases(
+    ctx: &Cli,
+    args: &PruneReleasesArgs,
+    today: NaiveDate,
+) -> Result<Vec<PruneReport>, AppError> {
+    let all = do_list_releases(ctx, &ListReleasesArgs::all(&args.project))?;
+    let mut report = vec![];
+    for release in select(args, all, today)? {
+        let unresolved = do_unresolved_issue_count(ctx, &release.id)?.issues_unresolved_count;
+        let mut entry = PruneReport {
+            release: release.name.clone().unwrap_or_default(),
+            id: release.id.clone(),
+            release_date: release.release_date,
+            action: "skipped",
+            reason: None,
+        };
+        if unresolved > 0 {
+            entry.reason = Some(format!("{} unresolved issue(s)", unresolved));
+        } else if args.dry_run {
+            entry.action = if args.delete {
+                "would delete"
+            } else {
+                "would archive"
+            };
+        } else if args.delete {
+            execute_delete_release(
+                ctx,
+                &DeleteReleaseArgs {
+                    release: release.id.clone(),
+                    project: args.project.clone(),
+                    by_id: Some(true),
+                    replace_fix_version: None,
+                    replace_affected_version: None,
+                },
+            )?;
+            entry.action = "deleted";
+        } else {
+            do_update_release(
+                ctx,
+                &UpdateReleaseArgs {
+                    name: None,
+                    description: None,
+                    start_date: None,
+                    release_date: None,
+                    is_released: None,
+                    archived: Some(true),
+                    by_id: Some(true),
+                    release: release.id.clone(),
+                    project: args.project.clone(),
+                },
+            )?;
+            entry.action = "archived";
+        }
+        info!("{} release {}", entry.action, entry.release);
+        report.push(entry);
+    }
+    Ok(report)
+}
+
+/// The released versions to prune, oldest first. Already archived versions are left out unless
+/// deleting.
+fn select(
+    args: &PruneReleasesArgs,
+    releases: Vec<Release>,
+    today: NaiveDate,
+) -> Result<Vec<Release>, AppError> {
+    let name_filter = match &args.name_filter {
+        Some(f) => Some(Regex::new(f).map_err(|e| AppError::InvalidReleaseName(e.to_string()))?),
+        None => None,
+    };
+    let mut candidates: Vec<(Option<NaiveDate>, Release)> = releases
+        .into_iter()
+        .filter(|r| r.released && (args.delete || !r.archived))
+        .filter(|r| match &name_filter {
+            Some(f) => r.name.as_deref().is_some_and(|n| f.is_match(n)),
+            None => true,
+        })
+        .map(|r| (r.release_date, r))
+        .collect();
+    // newest first, undated last, so keep_newest keeps the most recent releases
+    candidates.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    if let Some(keep) = args.keep_newest {
+        candidates = candidates.into_iter().skip(keep).collect();
+    }
+    if let Some(days) = args.older_than_days {
+        let cutoff = today
+            .checked_sub_days(Days::new(days))
+            .ok_or(AppError::DaysOutOfRange(days))?;
+        candidates.retain(|(date, _)| date.is_some_and(|d| d < cutoff));
+    }
+    Ok(candidates.into_iter().rev().map(|(_, r)| r).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::util::DATE_FORMAT;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn release(id: &str, name: &str, released: bool, date: Option<&str>) -> Release {
+        Release {
+            id: id.to_string(),
+            description: None,
+            name: Some(name.to_string()),
+            archived: false,
+            released,
+            start_date: None,
+            release_date: date.map(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).unwrap()),
+            overdue: None,
+            user_release_date: None,
+            project_id: 1,
+            issues_status_for_fix_version: None,
+            operations: None,
+        }
+    }
+
+    fn args() -> PruneReleasesArgs {
+        PruneReleasesArgs {
+            project: "FOO".to_string(),
+            older_than_days: None,
+            keep_newest: None,
+            name_filter: None,
+            delete: false,
+            dry_run: false,
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 6, 1).unwrap()
+    }
+
+    fn names(selected: Vec<Release>) -> Vec<String> {
+        selected.into_iter().filter_map(|r| r.name).collect()
+    }
+
+    fn releases() -> Vec<Release> {
+        vec![
+            release("1", "app-v1.0", true, Some("2023-01-01")),
+            release("2", "app-v1.1", true, Some("2023-03-01")),
+            release("3", "app-v1.2", true, Some("2023-05-20")),
+            release("4", "app-v1.3", false, Some("2023-01-15")),
+            release("5", "lib-v1.0", true, Some("2022-12-01")),
+        ]
+    }
+
+    #[test]
+    fn selects_by_age_and_name() {
+        let args = PruneReleasesArgs {
+            older_than_days: Some(30),
+            name_filter: Some("^app-".to_string()),
+            ..args()
+        };
+        let selected = select(&args, releases(), today()).unwrap();
+        assert_eq!(names(selected), vec!["app-v1.0", "app-v1.1"]);
+    }
+
+    #[test]
+    fn keeps_the_newest() {
+        let args = PruneReleasesArgs {
+            keep_newest: Some(2),
+            ..args()
+        };
+        let selected = select(&args, releases(), today()).unwrap();
+        assert_eq!(names(selected), vec!["lib-v1.0", "app-v1.0"]);
+    }
+
+    #[test]
+    fn rejects_ages_past_the_earliest_date() {
+        let args = PruneReleasesArgs {
+            older_than_days: Some(u64::MAX),
+            ..args()
+        };
+        assert!(matches!(
+            select(&args, releases(), today()),
+            Err(AppError::DaysOutOfRange(u64::MAX))
+        ));
+    }
+
+    #[test]
+    fn skips_releases_with_unresolved_issues() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/project/FOO/version"))
+                .respond_with(json_encoded(serde_json::json!({
+                    "total": 2,
+                    "startAt": 0,
+                    "isLast": true,
+                    "values": [
+                        {"id": "1", "name": "1.0", "archived": false, "released": true, "releaseDate": "2023-01-01", "projectId": 1},
+                        {"id": "2", "name": "1.1", "archived": false, "released": true, "releaseDate": "2023-02-01", "projectId": 1}
+                    ]
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/version/1/unresolvedIssueCount",
+            ))
+            .respond_with(json_encoded(serde_json::json!({
+                "issuesUnresolvedCount": 1,
+                "issuesCount": 4
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/version/2/unresolvedIssueCount",
+            ))
+            .respond_with(json_encoded(serde_json::json!({
+                "issuesUnresolvedCount": 0,
+                "issuesCount": 4
+            }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/version/2"),
+                request::body(json_decoded(eq(serde_json::json!({"archived": "true"})))),
+            ])
+            .respond_with(status_code(200)),
+        );
+
+        let args = PruneReleasesArgs {
+            older_than_days: Some(30),
+            ..args()
+        };
+        let report = do_prune_releases(&ctx, &args, today()).unwrap();
+        let actions: Vec<&str> = report.iter().map(|r| r.action).collect();
+        assert_eq!(actions, vec!["skipped", "archived"]);
+    }
+}
@@ -0,0 +1,254 @@
+use super::*;
+use crate::lib::commands::projects;
+use crate::lib::util::{PageBean, PageRequest, Paginator};
+use chrono::NaiveDate;
+use clap::{ArgGroup, Parser};
+use log::info;
+
+#[derive(Parser, Clone)]
+#[command(group(ArgGroup::new("targets").required(true).multiple(true).args(["projects", "category"]),))]
+pub struct SyncReleaseArgs {
+    #[arg(
+        long = "project",
+        short,
+        value_delimiter = ',',
+        help = "project to sync the release into; repeat or comma separate for several"
+    )]
+    pub(crate) projects: Vec<String>,
+    #[arg(
+        long,
+        short,
+        help = "sync into every project in this project category, by name or id"
+    )]
+    pub(crate) category: Option<String>,
+    #[arg(long, short, help = NAME_HELP)]
+    pub(crate) name: String,
+    #[arg(long, short, help = DESCRIPTION_HELP)]
+    pub(crate) description: Option<String>,
+    #[arg(long, short, value_parser = util::parse_date, help = START_DATE_HELP)]
+    pub(crate) start_date: Option<NaiveDate>,
+    #[arg(long, short, value_parser = util::parse_date, help = RELEASE_DATE_HELP)]
+    pub(crate) release_date: Option<NaiveDate>,
+}
+
+/// What happened to the release in one project.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    project: String,
+    id: Option<String>,
+    already_existed: bool,
+    action: &'static str,
+}
+
+#[derive(Deserialize, Debug)]
+struct ProjectKey {
+    key: String,
+}
+
+pub fn execute_sync_release(ctx: &Cli, args: &SyncReleaseArgs) -> Result<(), AppError> {
+    let report = do_sync_release(ctx, args)?;
+    util::format_print(report, ctx)
+}
+
+/// Creates the release in every project that lacks it and brings the dates and description of
+/// the existing ones in line.
+pub fn do_sync_release(ctx: &Cli, args: &SyncReleaseArgs) -> Result<Vec<SyncReport>, AppError> {
+    let mut projects = args.projects.clone();
+    if let Some(category) = &args.category {
+        for project in category_projects(ctx, category)? {
+            if !projects.contains(&project) {
+                projects.push(project);
+            }
+        }
+    }
+
+    let mut report = vec![];
+    for project in projects {
+        let entry = match find_release_by_name(ctx, project.clone(), args.name.clone())? {
+            Some(existing) => sync_existing(ctx, args, &project, existing)?,
+            None => {
+                let created = do_create_release(
+                    ctx,
+                    &CreateReleaseArgs {
+                        project: project.clone(),
+                        name: args.name.clone(),
+                        description: args.description.clone(),
+                        start_date: args.start_date,
+                        release_date: args.release_date,
+                    },
+                )?;
+                SyncReport {
+                    project: project.clone(),
+                    id: created.map(|r| r.id),
+                    already_existed: false,
+                    action: "created",
+                }
+            }
+        };
+        info!("{} release {} in {}", entry.action, args.name, project);
+        report.push(entry);
+    }
+    Ok(report)
+}
+
+/// Updates only the fields that were given and differ, so matching releases aren't touched.
+fn sync_existing(
+    ctx: &Cli,
+    args: &SyncReleaseArgs,
+    project: &str,
+    existing: Release,
+) -> Result<SyncReport, AppError> {
+    let update = UpdateReleaseArgs {
+        name: None,
+        description: changed(&args.description, &existing.description),
+        start_date: changed(&args.start_date, &existing.start_date),
+        release_date: changed(&args.release_date, &existing.release_date),
+        is_released: None,
+        archived: None,
+        by_id: Some(true),
+        release: existing.id.clone(),
+        project: project.to_string(),
+    };
+    let action = if update.description.is_some()
+        || update.start_date.is_some()
+        || update.release_date.is_some()
+    {
+        do_update_release(ctx, &update)?;
+        "updated"
+    } else {
+        "unchanged"
+    };
+    Ok(SyncReport {
+        project: project.to_string(),
+        id: Some(existing.id),
+        already_existed: true,
+        action,
+    })
+}
+
+/// The keys of every project in a category, given by its id or its name.
+fn category_projects(ctx: &Cli, category: &str) -> Result<Vec<String>, AppError> {
+    let id = projects::category_id(ctx, category)?;
+    let req_url = format!("{}/rest/api/3/project/search", ctx.base_jira_url);
+    Paginator::new(0, 50, true, |page: &PageRequest| {
+        let mut query_params = HashMap::<&str, String>::new();
+        query_params.insert("categoryId", id.clone());
+        query_params.insert("startAt", page.start_at.to_string());
+        query_params.insert("maxResults", page.max_results.to_string());
+        util::do_get::<PageBean<ProjectKey>, HashMap<&str, String>>(&req_url, ctx, query_params)
+    })
+    .map(|p| p.map(|p| p.key))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    fn versions(values: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({"total": 1, "startAt": 0, "isLast": true, "values": values})
+    }
+
+    #[test]
+    fn creates_where_missing_and_updates_what_differs() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/projectCategory"))
+                .respond_with(json_encoded(serde_json::json!([
+                    {"id": "10", "name": "Product"},
+                    {"id": "11", "name": "Internal"}
+                ]))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/project/search"),
+                request::query(url_decoded(contains(("categoryId", "10")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "total": 2,
+                "startAt": 0,
+                "isLast": true,
+                "values": [{"key": "BE"}, {"key": "FE"}]
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/FE/version",
+            ))
+            .respond_with(json_encoded(versions(serde_json::json!([
+                {"id": "1", "name": "2.0", "description": "shared", "releaseDate": "2023-05-01",
+                 "archived": false, "released": false, "projectId": 1}
+            ])))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/BE/version",
+            ))
+            .respond_with(json_encoded(versions(serde_json::json!([
+                {"id": "2", "name": "2.0", "releaseDate": "2023-04-01",
+                 "archived": false, "released": false, "projectId": 2}
+            ])))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path(
+                "GET",
+                "/rest/api/3/project/MOB/version",
+            ))
+            .respond_with(json_encoded(versions(serde_json::json!([])))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/version/2"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "description": "shared",
+                    "releaseDate": "2023-05-01"
+                })))),
+            ])
+            .respond_with(status_code(200)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/rest/api/3/version"),
+                request::body(json_decoded(eq(serde_json::json!({
+                    "project": "MOB",
+                    "name": "2.0",
+                    "description": "shared",
+                    "releaseDate": "2023-05-01"
+                })))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "id": "3", "name": "2.0", "archived": false, "released": false, "projectId": 3
+            }))),
+        );
+
+        let args = SyncReleaseArgs {
+            projects: vec!["FE".to_string(), "MOB".to_string()],
+            category: Some("product".to_string()),
+            name: "2.0".to_string(),
+            description: Some("shared".to_string()),
+            start_date: None,
+            release_date: NaiveDate::from_ymd_opt(2023, 5, 1),
+        };
+        let report = do_sync_release(&ctx, &args).unwrap();
+        let summary: Vec<(&str, bool, &str)> = report
+            .iter()
+            .map(|r| (r.project.as_str(), r.already_existed, r.action))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("FE", true, "unchanged"),
+                ("MOB", false, "created"),
+                ("BE", true, "updated"),
+            ]
+        );
+        assert_eq!(report[1].id.as_deref(), Some("3"));
+    }
+}
@@ -1,6 +1,7 @@
 use super::*;
 use crate::lib::{util, AppError};
 use crate::Cli;
+use chrono::NaiveDate;
 use clap::Parser;
 use std::collections::HashMap;
 
@@ -11,12 +12,14 @@ pub struct UpdateReleaseArgs {
     pub(crate) name: Option<String>,
     #[arg(long, short, help = DESCRIPTION_HELP)]
     pub(crate) description: Option<String>,
-    #[arg(long, help = START_DATE_HELP)]
-    pub(crate) start_date: Option<String>,
-    #[arg(long, help = RELEASE_DATE_HELP)]
-    pub(crate) release_date: Option<String>,
+    #[arg(long, value_parser = util::parse_date, help = START_DATE_HELP)]
+    pub(crate) start_date: Option<NaiveDate>,
+    #[arg(long, value_parser = util::parse_date, help = RELEASE_DATE_HELP)]
+    pub(crate) release_date: Option<NaiveDate>,
     #[arg(long, short, help = "whether or not this release has been 'released'")]
     pub(crate) is_released: Option<bool>,
+    #[arg(long, help = "archive (true) or unarchive (false) this release")]
+    pub(crate) archived: Option<bool>,
     #[arg(long, short, help = BY_ID_HELP)]
     pub(crate) by_id: Option<bool>,
     #[arg(long, short, help = "the name (or id) of the release to update")]
@@ -37,7 +40,7 @@ pub fn execute_update_release(ctx: &Cli, args: &UpdateReleaseArgs) -> Result<(),
 
 pub fn do_update_release(ctx: &Cli, args: &UpdateReleaseArgs) -> Result<(), AppError> {
     let mut mutargs = args.clone();
-    if args.by_id.is_some() && args.by_id.unwrap() == true {
+    if args.by_id == Some(true) {
         let req_url = format!("{}/rest/api/3/version/{}", ctx.base_jira_url, args.release);
 
         util::do_put::<(), HashMap<&str, String>>(
@@ -63,21 +66,24 @@ pub fn do_update_release(ctx: &Cli, args: &UpdateReleaseArgs) -> Result<(), AppE
 
 fn assemble_update_args(args: UpdateReleaseArgs) -> HashMap<&'static str, String> {
     let mut params: HashMap<&str, String> = HashMap::new();
-    if args.start_date.is_some() {
-        params.insert("startDate", args.start_date.unwrap());
+    if let Some(start_date) = args.start_date {
+        params.insert("startDate", start_date.to_string());
     }
-    if args.release_date.is_some() {
-        params.insert("releaseDate", args.release_date.unwrap());
+    if let Some(release_date) = args.release_date {
+        params.insert("releaseDate", release_date.to_string());
     }
-    if args.description.is_some() {
-        params.insert("description", args.description.unwrap());
+    if let Some(description) = args.description {
+        params.insert("description", description);
     }
-    if args.name.is_some() {
-        params.insert("name", args.name.unwrap());
+    if let Some(name) = args.name {
+        params.insert("name", name);
     }
-    if args.is_released.is_some() {
-        params.insert("released", args.is_released.unwrap().to_string());
+    if let Some(is_released) = args.is_released {
+        params.insert("released", is_released.to_string());
+    }
+    if let Some(archived) = args.archived {
+        params.insert("archived", archived.to_string());
     }
 
-    return params;
+    params
 }
@@ -0,0 +1,58 @@
+use super::*;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct AssignIssueArgs {
+    #[arg(long, short, help = "the ticket name ex. FOO-1234")]
+    pub(crate) name: String,
+    #[arg(long, short, help = ASSIGNEE_HELP)]
+    pub(crate) assignee: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct AssignIssueRequest {
+    /// null unassigns the issue
+    account_id: Option<String>,
+}
+
+pub fn execute_assign_issue(ctx: &Cli, args: &AssignIssueArgs) -> Result<(), AppError> {
+    let account_id = resolve_assignee(ctx, &args.assignee)?;
+    do_assign_issue(ctx, &args.name, account_id)
+}
+
+pub fn do_assign_issue(ctx: &Cli, issue: &str, account_id: Option<String>) -> Result<(), AppError> {
+    let req_url = format!("{}/rest/api/3/issue/{}/assignee", ctx.base_jira_url, issue);
+    util::do_put::<(), AssignIssueRequest>(&req_url, ctx, &AssignIssueRequest { account_id })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn unassigns_with_a_null_account() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/rest/api/3/issue/FOO-1/assignee"),
+                request::body(json_decoded(eq(serde_json::json!({"accountId": null})))),
+            ])
+            .respond_with(status_code(204)),
+        );
+
+        execute_assign_issue(
+            &ctx,
+            &AssignIssueArgs {
+                name: "FOO-1".to_string(),
+                assignee: "unassigned".to_string(),
+            },
+        )
+        .unwrap();
+    }
+}
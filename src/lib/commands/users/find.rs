@@ -0,0 +1,23 @@
+use super::*;
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct FindUserArgs {
+    /// an email address or part of a display name
+    pub query: String,
+    #[arg(long, default_value_t = 50, help = "the most users to return")]
+    pub(crate) max_items: u64,
+}
+
+pub fn execute_find_user(ctx: &Cli, args: &FindUserArgs) -> Result<(), AppError> {
+    util::format_print(do_find_users(ctx, &args.query, args.max_items)?, ctx)
+}
+
+/// Users whose email address or display name matches `query`, as jira ranks them.
+pub fn do_find_users(ctx: &Cli, query: &str, max_items: u64) -> Result<Vec<User>, AppError> {
+    let req_url = format!("{}/rest/api/3/user/search", ctx.base_jira_url);
+    let mut query_params = HashMap::<&str, String>::new();
+    query_params.insert("query", query.to_string());
+    query_params.insert("maxResults", max_items.to_string());
+    util::do_get::<Vec<User>, HashMap<&str, String>>(&req_url, ctx, query_params)
+}
@@ -0,0 +1,176 @@
+use log::debug;
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+pub use assign::*;
+pub use find::*;
+
+use crate::lib::{util, AppError};
+use crate::Cli;
+use clap::Parser;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+mod assign;
+mod find;
+
+pub const ASSIGNEE_HELP: &str =
+    "who to assign: an email address, display name, account id, `me` or `unassigned`";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub(crate) account_id: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) email_address: Option<String>,
+    #[serde(default)]
+    pub(crate) active: bool,
+    pub(crate) account_type: Option<String>,
+    pub(crate) time_zone: Option<String>,
+}
+
+#[derive(Parser, Clone)]
+#[command()]
+pub struct WhoamiArgs {}
+
+pub fn execute_whoami(ctx: &Cli, _args: &WhoamiArgs) -> Result<(), AppError> {
+    util::format_print(vec![do_myself(ctx)?], ctx)
+}
+
+/// The user the credentials in `ctx` belong to.
+pub fn do_myself(ctx: &Cli) -> Result<User, AppError> {
+    let req_url = format!("{}/rest/api/3/myself", ctx.base_jira_url);
+    util::do_get::<User, HashMap<&str, String>>(&req_url, ctx, HashMap::new())
+}
+
+/// The account id `who` refers to, or `None` for `unassigned`.
+pub fn resolve_assignee(ctx: &Cli, who: &str) -> Result<Option<String>, AppError> {
+    static ACCOUNT_ID: OnceLock<Regex> = OnceLock::new();
+    let account_id =
+        ACCOUNT_ID.get_or_init(|| Regex::new(r"^([0-9a-f]{24}|[0-9a-z]+:[0-9a-f-]{36})$").unwrap());
+    match who.trim() {
+        "unassigned" | "none" => Ok(None),
+        "me" => Ok(Some(do_myself(ctx)?.account_id)),
+        id if account_id.is_match(id) => Ok(Some(id.to_string())),
+        query => {
+            let found = do_find_users(ctx, query, 50)?;
+            debug!("{} users match {}", found.len(), query);
+            let exact: Vec<&User> = found
+                .iter()
+                .filter(|u| {
+                    u.email_address
+                        .as_deref()
+                        .is_some_and(|e| e.eq_ignore_ascii_case(query))
+                        || u.display_name
+                            .as_deref()
+                            .is_some_and(|n| n.eq_ignore_ascii_case(query))
+                })
+                .collect();
+            // jira hides most users' email addresses, so a lone hit for an email query is it;
+            // a lone hit for a name only contains the query somewhere and may be someone else
+            let candidates: Vec<&User> =
+                if exact.is_empty() && found.len() == 1 && query.contains('@') {
+                    found.iter().collect()
+                } else {
+                    exact
+                };
+            match candidates.as_slice() {
+                [user] => Ok(Some(user.account_id.clone())),
+                [] => Err(AppError::UserNotFound(query.to_string())),
+                _ => Err(AppError::AmbiguousUser(
+                    query.to_string(),
+                    candidates
+                        .iter()
+                        .map(|u| u.account_id.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", "),
+                )),
+            }
+        }
+    }
+}
+
+/// A jql clause matching the issues assigned to `who`.
+pub fn assignee_jql(ctx: &Cli, who: &str) -> Result<String, AppError> {
+    Ok(match resolve_assignee(ctx, who)? {
+        Some(id) => format!("assignee = \"{}\"", id),
+        None => "assignee is EMPTY".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::{matchers::*, responders::*, Expectation, ServerPool};
+
+    static SERVER_POOL: ServerPool = ServerPool::new(1);
+
+    #[test]
+    fn resolves_assignees() {
+        let server = SERVER_POOL.get_server();
+        let ctx = Cli::for_tests(server.url("").to_string());
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/rest/api/3/myself")).respond_with(
+                json_encoded(serde_json::json!({
+                    "accountId": "5b10a2844c20165700ede21g",
+                    "displayName": "Me",
+                    "active": true
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/user/search"),
+                request::query(url_decoded(contains(("query", "jane@example.com")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!([
+                {"accountId": "1:11111111-1111-1111-1111-111111111111", "displayName": "Jane", "active": true}
+            ]))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/user/search"),
+                request::query(url_decoded(contains(("query", "Sam")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!([
+                {"accountId": "a", "displayName": "Sam", "active": true},
+                {"accountId": "b", "displayName": "Sam", "active": false},
+                {"accountId": "c", "displayName": "Samantha", "active": true}
+            ]))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/rest/api/3/user/search"),
+                request::query(url_decoded(contains(("query", "Jo")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!([
+                {"accountId": "d", "displayName": "Joanna", "active": true}
+            ]))),
+        );
+
+        assert_eq!(resolve_assignee(&ctx, "unassigned").unwrap(), None);
+        assert_eq!(
+            resolve_assignee(&ctx, "me").unwrap().as_deref(),
+            Some("5b10a2844c20165700ede21g")
+        );
+        assert_eq!(
+            resolve_assignee(&ctx, "jane@example.com")
+                .unwrap()
+                .as_deref(),
+            Some("1:11111111-1111-1111-1111-111111111111")
+        );
+        assert_eq!(
+            assignee_jql(&ctx, "5b10a2844c20165700ede21f").unwrap(),
+            "assignee = \"5b10a2844c20165700ede21f\""
+        );
+        assert!(matches!(
+            resolve_assignee(&ctx, "Sam"),
+            Err(AppError::AmbiguousUser(_, _))
+        ));
+        assert!(matches!(
+            resolve_assignee(&ctx, "Jo"),
+            Err(AppError::UserNotFound(_))
+        ));
+    }
+}
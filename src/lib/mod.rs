@@ -11,10 +11,14 @@ pub enum AppError {
     ApiCallFailed(#[from] reqwest::Error),
     #[error("failed calling jira {0}")]
     ApiCallBadStatus(String),
+    #[error("jira rejected the request: {}", .0.join(" "))]
+    ApiCallRejected(Vec<String>),
     #[error("IO error")]
     IOError(#[from] std::io::Error),
     #[error("failed deserializing response")]
     DeserializationError,
+    #[error("failed formatting output {0}")]
+    SerializationError(String),
 
     #[error("query was meant to match exactly one release but found multiple")]
     MatchedMultipleReleases,
@@ -24,4 +28,46 @@ pub enum AppError {
     UnknownTransition,
     #[error("no issues were found to release")]
     NoIssuesFound,
+    #[error("{0} issue(s) could not be tagged; the release was rolled back")]
+    ReleaseRolledBack(usize),
+    #[error("{0} issue(s) could not be tagged and the rollback could not delete release(s) {1}; delete them by hand")]
+    ReleaseRollbackIncomplete(usize, String),
+    #[error("could not use release journal {0}")]
+    InvalidJournal(String),
+    #[error("could not use release plan {0}")]
+    InvalidPlan(String),
+    #[error("jira no longer matches the release plan: {0}")]
+    PlanDrift(String),
+    #[error("invalid pipeline: {0}")]
+    InvalidPipeline(String),
+    #[error("pipeline step {0} failed: {1}")]
+    PipelineStepFailed(String, String),
+    #[error("cannot work out the release name: {0}")]
+    InvalidReleaseName(String),
+    #[error("no release named {0}")]
+    ReleaseNotFound(String),
+    #[error("cannot merge release {0} into itself")]
+    MergeIntoItself(String),
+    #[error("{0} days ago is before the earliest date supported")]
+    DaysOutOfRange(u64),
+    #[error("release is not ready: {0}")]
+    ReleaseNotReady(String, i32),
+    #[error("could not read git history: {0}")]
+    GitError(String),
+    #[error("no project category named {0}")]
+    ProjectCategoryNotFound(String),
+    #[error("no component named {0}")]
+    ComponentNotFound(String),
+    #[error("no field named {0}")]
+    UnknownField(String),
+    #[error("more than one field is named {0}; use one of their ids instead: {1}")]
+    AmbiguousField(String, String),
+    #[error("invalid field value: {0}")]
+    InvalidFieldValue(String),
+    #[error("no user matching {0}")]
+    UserNotFound(String),
+    #[error("more than one user matches {0}; use one of their account ids instead: {1}")]
+    AmbiguousUser(String, String),
+    #[error("{0} check(s) failed")]
+    ChecksFailed(usize),
 }
@@ -0,0 +1,122 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parses a date argument, relative to today. Used as a clap value parser.
+pub fn parse_date(input: &str) -> Result<NaiveDate, String> {
+    parse_date_from(input, Utc::now().date_naive())
+}
+
+/// Accepts yyyy-mm-dd, `today`, `yesterday`, `tomorrow`, an offset such as `+2w` or `-3d`
+/// (d, w, m or y), or `next friday` / `last monday`.
+pub fn parse_date_from(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim().to_lowercase();
+    if let Ok(date) = NaiveDate::parse_from_str(&input, DATE_FORMAT) {
+        return Ok(date);
+    }
+    let invalid = || {
+        format!(
+            "'{}' is not a date; use yyyy-mm-dd, today, +2w, -3d or next friday",
+            input
+        )
+    };
+    match input.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        _ => {}
+    }
+    if let Some((direction, day)) = input.split_once(' ') {
+        let day: Weekday = day.trim().parse().map_err(|_| invalid())?;
+        return match direction {
+            "next" => Ok(step_to(today, day, 1)),
+            "last" => Ok(step_to(today, day, -1)),
+            _ => Err(invalid()),
+        };
+    }
+    offset(&input, today).ok_or_else(invalid)
+}
+
+/// The closest `day` strictly after (or before, going back) `today`.
+fn step_to(today: NaiveDate, day: Weekday, step: i64) -> NaiveDate {
+    let mut date = today + Duration::days(step);
+    while date.weekday() != day {
+        date += Duration::days(step);
+    }
+    date
+}
+
+fn offset(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let (sign, rest) = match input.as_bytes().first()? {
+        b'+' => (1, &input[1..]),
+        b'-' => (-1, &input[1..]),
+        _ => return None,
+    };
+    let unit = rest.chars().last()?;
+    let amount: u32 = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+    let months = |m: u32| {
+        if sign > 0 {
+            today.checked_add_months(Months::new(m))
+        } else {
+            today.checked_sub_months(Months::new(m))
+        }
+    };
+    match unit {
+        'd' => today.checked_add_signed(Duration::days(sign * amount as i64)),
+        'w' => today.checked_add_signed(Duration::weeks(sign * amount as i64)),
+        'm' => months(amount),
+        'y' => months(amount.checked_mul(12)?),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn parses_absolute_and_relative_dates() {
+        // a wednesday
+        let today = date("2023-05-31");
+        let cases = [
+            ("2023-01-02", "2023-01-02"),
+            ("today", "2023-05-31"),
+            ("Tomorrow", "2023-06-01"),
+            ("+2w", "2023-06-14"),
+            ("-3d", "2023-05-28"),
+            ("+1m", "2023-06-30"),
+            ("+1y", "2024-05-31"),
+            ("next friday", "2023-06-02"),
+            ("next wednesday", "2023-06-07"),
+            ("last monday", "2023-05-29"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_date_from(input, today),
+                Ok(date(expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        let today = date("2023-05-31");
+        for input in [
+            "2023-02-30",
+            "soon",
+            "+2",
+            "+w",
+            "next fortnight",
+            "2w",
+            "+999999999d",
+        ] {
+            assert!(parse_date_from(input, today).is_err(), "{}", input);
+        }
+    }
+}
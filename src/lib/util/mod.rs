@@ -1,37 +1,25 @@
+mod dates;
+mod output;
+mod paginate;
+
+pub use dates::*;
+pub use output::*;
+pub use paginate::*;
+
 use super::AppError;
 use crate::Cli;
+use base64::Engine;
 use log::trace;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::from_str;
 use std::any::TypeId;
-use std::io;
 use std::option::Option;
 
-#[derive(clap::ValueEnum, Debug, Copy, Clone)]
-pub enum Format {
-    Csv,
-    Json,
-}
-
-pub fn format_print<T: Serialize>(items: Vec<T>, format: Format) -> Result<(), AppError> {
-    match format {
-        Format::Csv => {
-            let mut writer = csv::WriterBuilder::new()
-                .has_headers(true)
-                .from_writer(io::stdout());
-            items.iter().for_each(|i| {
-                writer.serialize(i).expect("TODO: panic message");
-            });
-            writer.flush()?;
-        }
-        Format::Json => {
-            let json = serde_json::to_string(&items).expect("TODO: panic message");
-            println!("{}", json);
-        }
-    }
-
-    Ok(())
+fn basic_auth(ctx: &Cli) -> String {
+    let token = base64::engine::general_purpose::STANDARD
+        .encode(format!("{}:{}", ctx.user_email, ctx.auth_token));
+    format!("Basic {}", token)
 }
 
 pub fn do_get<T: DeserializeOwned, S: Serialize>(
@@ -40,13 +28,32 @@ pub fn do_get<T: DeserializeOwned, S: Serialize>(
     query_params: S,
 ) -> Result<T, AppError> {
     let client = reqwest::blocking::Client::new();
-    let token = base64::encode(format!("{}:{}", ctx.user_email, ctx.auth_token));
 
     let res = client
         .get(req_url)
         .query(&query_params)
         .header("Content-Type", "application/json")
-        .header("Authorization", format!("Basic {}", token))
+        .header("Authorization", basic_auth(ctx))
+        .send()?;
+    if !res.status().is_success() {
+        return Err(AppError::ApiCallBadStatus(format!(
+            "status code {}",
+            res.status().as_str()
+        )));
+    }
+    match res.json::<T>() {
+        Ok(r) => Ok(r),
+        Err(_) => Err(AppError::DeserializationError),
+    }
+}
+
+/// Like `do_get` but without credentials, for checking jira apart from the user's token.
+pub fn do_get_anonymous<T: DeserializeOwned>(req_url: &String) -> Result<T, AppError> {
+    let client = reqwest::blocking::Client::new();
+
+    let res = client
+        .get(req_url)
+        .header("Content-Type", "application/json")
         .send()?;
     if !res.status().is_success() {
         return Err(AppError::ApiCallBadStatus(format!(
@@ -67,51 +74,65 @@ pub fn do_post<T: DeserializeOwned + 'static, S: Serialize>(
 ) -> Result<Option<T>, AppError> {
     let client = reqwest::blocking::Client::new();
 
-    let token = base64::encode(format!("{}:{}", ctx.user_email, ctx.auth_token));
     let res = client
         .post(req_url)
         .body(serde_json::to_string(post_body).unwrap())
         .header("Content-Type", "application/json")
-        .header("Authorization", format!("Basic {}", token))
+        .header("Authorization", basic_auth(ctx))
         .send()?;
 
     trace!("{:?}", serde_json::json!(post_body));
-    trace!("{:?}", res.status());
-    if !res.status().is_success() {
-        return Err(AppError::ApiCallBadStatus(format!(
-            "status code {}",
-            res.status().as_str()
-        )));
-    }
+    let status = res.status();
+    trace!("{:?}", status);
     let body = res.text()?;
 
     trace!("{:?}", body);
+    if !status.is_success() {
+        return Err(rejection(status, &body));
+    }
 
     if TypeId::of::<T>() == TypeId::of::<()>() {
         Ok(None)
     } else {
-        match from_str::<T>(&*body) {
+        match from_str::<T>(&body) {
             Ok(r) => Ok(Some(r)),
             Err(_) => Err(AppError::DeserializationError),
         }
     }
 }
 
+/// The error for a failed call; a 400 carries jira's reasons, when it gives any.
+fn rejection(status: reqwest::StatusCode, body: &str) -> AppError {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ErrorCollection {
+        #[serde(default)]
+        error_messages: Vec<String>,
+    }
+    if status == reqwest::StatusCode::BAD_REQUEST {
+        if let Ok(errors) = from_str::<ErrorCollection>(body) {
+            if !errors.error_messages.is_empty() {
+                return AppError::ApiCallRejected(errors.error_messages);
+            }
+        }
+    }
+    AppError::ApiCallBadStatus(format!("status code {}", status.as_str()))
+}
+
 pub fn do_put<T: DeserializeOwned + 'static, S: Serialize>(
     req_url: &String,
     ctx: &Cli,
     put_body: &S,
 ) -> Result<Option<T>, AppError> {
     let client = reqwest::blocking::Client::new();
-    let token = base64::encode(format!("{}:{}", ctx.user_email, ctx.auth_token));
     let res = client
         .put(req_url)
         .body(serde_json::to_string(put_body).unwrap())
         .header("Content-Type", "application/json")
-        .header("Authorization", format!("Basic {}", token))
+        .header("Authorization", basic_auth(ctx))
         .send()?;
     trace!("{:?}", serde_json::json!(put_body));
-    let status = res.status().clone();
+    let status = res.status();
     let body = res.text()?;
     trace!("{:?}", body);
     if !status.is_success() {
@@ -123,20 +144,42 @@ pub fn do_put<T: DeserializeOwned + 'static, S: Serialize>(
     if TypeId::of::<T>() == TypeId::of::<()>() {
         Ok(None)
     } else {
-        match from_str::<T>(&*body) {
+        match from_str::<T>(&body) {
             Ok(r) => Ok(Some(r)),
             Err(_) => Err(AppError::DeserializationError),
         }
     }
 }
 
+pub fn do_delete<S: Serialize>(
+    req_url: &String,
+    ctx: &Cli,
+    query_params: S,
+) -> Result<(), AppError> {
+    let client = reqwest::blocking::Client::new();
+    let res = client
+        .delete(req_url)
+        .query(&query_params)
+        .header("Content-Type", "application/json")
+        .header("Authorization", basic_auth(ctx))
+        .send()?;
+    trace!("{:?}", res.status());
+    if !res.status().is_success() {
+        return Err(AppError::ApiCallBadStatus(format!(
+            "status code {}",
+            res.status().as_str()
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
     use std::collections::HashMap;
 
     use crate::lib::commands::releases::Release;
-    use crate::lib::util::{do_get, Format};
+    use crate::lib::util::do_get;
     use crate::Cli;
     use httptest::{matchers::*, responders::*, Expectation, ServerPool};
 
@@ -145,13 +188,7 @@ mod tests {
     #[test]
     fn test_deser_error() -> Result<(), Box<dyn std::error::Error>> {
         let server = SERVER_POOL.get_server();
-        let ctx = Cli {
-            auth_token: "".to_string(),
-            output_format: Format::Csv,
-            user_email: "".to_string(),
-            base_jira_url: "".to_string(),
-            command: None,
-        };
+        let ctx = Cli::for_tests("".to_string());
         let params: HashMap<&str, &str> = HashMap::new();
         let url = server.url("/foo");
         server.expect(Expectation::matching(any()).respond_with(json_encoded("")));
@@ -165,13 +202,7 @@ mod tests {
     #[test]
     fn test_server_error() -> Result<(), Box<dyn std::error::Error>> {
         let server = SERVER_POOL.get_server();
-        let ctx = Cli {
-            auth_token: "".to_string(),
-            output_format: Format::Csv,
-            user_email: "".to_string(),
-            base_jira_url: "".to_string(),
-            command: None,
-        };
+        let ctx = Cli::for_tests("".to_string());
         let params: HashMap<&str, &str> = HashMap::new();
         let url = server.url("/foo");
         server.expect(Expectation::matching(any()).respond_with(status_code(500)));
@@ -0,0 +1,577 @@
+use super::super::AppError;
+use crate::Cli;
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::io;
+use std::io::Write;
+
+const TRUNCATION_MARKER: char = '…';
+const TABLE_COLUMN_GAP: usize = 2;
+const TABLE_MIN_COLUMN_WIDTH: usize = 4;
+
+#[derive(clap::ValueEnum, Debug, Copy, Clone)]
+pub enum Format {
+    Csv,
+    Json,
+    Table,
+    Yaml,
+    Ndjson,
+    Markdown,
+}
+
+impl Format {
+    /// whether the format can only hold flat rows of scalar values
+    pub fn is_tabular(&self) -> bool {
+        matches!(self, Format::Csv | Format::Table | Format::Markdown)
+    }
+}
+
+/// How items handed to `format_print` are rendered, taken from the global cli options.
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    pub format: Format,
+    pub columns: Option<Vec<String>>,
+    pub sort_by: Option<String>,
+    pub descending: bool,
+}
+
+impl From<&Cli> for OutputOptions {
+    fn from(ctx: &Cli) -> Self {
+        OutputOptions {
+            format: ctx.output_format,
+            columns: ctx.columns.clone(),
+            sort_by: ctx.sort_by.clone(),
+            descending: ctx.desc,
+        }
+    }
+}
+
+pub fn format_print<T: Serialize>(items: Vec<T>, ctx: &Cli) -> Result<(), AppError> {
+    format_stream(items.into_iter().map(Ok), ctx)
+}
+
+/// Writes items as they are produced, so long paged listings start printing immediately.
+/// Sorting and the table format need every item first and fall back to collecting.
+pub fn format_stream<T: Serialize, I: IntoIterator<Item = Result<T, AppError>>>(
+    items: I,
+    ctx: &Cli,
+) -> Result<(), AppError> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write_stream(items, &OutputOptions::from(ctx), &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_stream<T, I, W>(
+    items: I,
+    options: &OutputOptions,
+    out: &mut W,
+) -> Result<(), AppError>
+where
+    T: Serialize,
+    I: IntoIterator<Item = Result<T, AppError>>,
+    W: Write,
+{
+    let values = items.into_iter().map(|i| i.and_then(|item| to_value(&item)));
+
+    if options.sort_by.is_some() || matches!(options.format, Format::Table) {
+        let mut all = values.collect::<Result<Vec<Value>, AppError>>()?;
+        if let Some(sort_by) = &options.sort_by {
+            sort(&mut all, sort_by, options.descending);
+        }
+        let projected: Vec<Value> = all.into_iter().map(|v| project(v, options)).collect();
+        if matches!(options.format, Format::Table) {
+            let (headers, rows) = to_rows(&projected);
+            return write_table(&headers, &rows, terminal_width(), out);
+        }
+        let mut writer = RowWriter::new(options.format, out);
+        for v in &projected {
+            writer.write(v)?;
+        }
+        return writer.finish();
+    }
+
+    let mut writer = RowWriter::new(options.format, out);
+    for v in values {
+        writer.write(&project(v?, options))?;
+    }
+    writer.finish()
+}
+
+fn to_value<T: Serialize>(item: &T) -> Result<Value, AppError> {
+    serde_json::to_value(item).map_err(|e| AppError::SerializationError(e.to_string()))
+}
+
+/// Writes one item at a time for every format except `Table`.
+/// Row based formats take their header from the first item.
+struct RowWriter<'w, W: Write> {
+    format: Format,
+    out: &'w mut W,
+    headers: Option<Vec<String>>,
+    written: usize,
+}
+
+impl<'w, W: Write> RowWriter<'w, W> {
+    fn new(format: Format, out: &'w mut W) -> RowWriter<'w, W> {
+        RowWriter {
+            format,
+            out,
+            headers: None,
+            written: 0,
+        }
+    }
+
+    fn write(&mut self, value: &Value) -> Result<(), AppError> {
+        match self.format {
+            Format::Csv => {
+                let headers = self.headers_for(value)?;
+                let row = row_cells(value, &headers);
+                self.write_csv_record(&row)?;
+            }
+            Format::Markdown => {
+                let headers = self.headers_for(value)?;
+                let row = row_cells(value, &headers);
+                write_markdown_row(&row, self.out)?;
+            }
+            Format::Json => {
+                write!(self.out, "{}", if self.written == 0 { "[" } else { "," })?;
+                serde_json::to_writer(&mut *self.out, value)
+                    .map_err(|e| AppError::SerializationError(e.to_string()))?;
+            }
+            Format::Ndjson => {
+                serde_json::to_writer(&mut *self.out, value)
+                    .map_err(|e| AppError::SerializationError(e.to_string()))?;
+                writeln!(self.out)?;
+            }
+            Format::Yaml => {
+                serde_yaml::to_writer(&mut *self.out, &[value])
+                    .map_err(|e| AppError::SerializationError(e.to_string()))?;
+            }
+            Format::Table => unreachable!("tables are written once every row is known"),
+        }
+        self.written += 1;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), AppError> {
+        match self.format {
+            Format::Json if self.written == 0 => writeln!(self.out, "[]")?,
+            Format::Json => writeln!(self.out, "]")?,
+            Format::Yaml if self.written == 0 => writeln!(self.out, "[]")?,
+            _ => {}
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Returns the header row, writing it out first if this is the first item.
+    fn headers_for(&mut self, value: &Value) -> Result<Vec<String>, AppError> {
+        if let Some(headers) = &self.headers {
+            return Ok(headers.clone());
+        }
+        let headers = headers_of(value);
+        match self.format {
+            Format::Csv => self.write_csv_record(&headers)?,
+            Format::Markdown => write_markdown_header(&headers, self.out)?,
+            _ => {}
+        }
+        self.headers = Some(headers.clone());
+        Ok(headers)
+    }
+
+    fn write_csv_record(&mut self, record: &[String]) -> Result<(), AppError> {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(&mut *self.out);
+        writer
+            .write_record(record)
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Looks up a dotted path such as `fields.status.name` in a json value.
+/// Numeric segments index into arrays.
+pub(crate) fn value_at_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |current, segment| match current {
+        Value::Object(map) => map.get(segment),
+        Value::Array(list) => segment.parse::<usize>().ok().and_then(|i| list.get(i)),
+        _ => None,
+    })
+}
+
+fn sort(values: &mut [Value], sort_by: &str, descending: bool) {
+    values.sort_by(|a, b| {
+        let ordering = compare_values(value_at_path(a, sort_by), value_at_path(b, sort_by));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Applies `--columns`, emitting a flat object keyed by the requested paths.
+fn project(value: Value, options: &OutputOptions) -> Value {
+    match &options.columns {
+        None => value,
+        Some(columns) => {
+            let mut selected = serde_json::Map::new();
+            for c in columns {
+                selected.insert(
+                    c.clone(),
+                    value_at_path(&value, c).cloned().unwrap_or(Value::Null),
+                );
+            }
+            Value::Object(selected)
+        }
+    }
+}
+
+/// Orders missing values first, then booleans, numbers and strings.
+fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    fn rank(v: Option<&Value>) -> u8 {
+        match v {
+            None | Some(Value::Null) => 0,
+            Some(Value::Bool(_)) => 1,
+            Some(Value::Number(_)) => 2,
+            Some(Value::String(_)) => 3,
+            Some(_) => 4,
+        }
+    }
+    match (a, b) {
+        (Some(Value::Bool(x)), Some(Value::Bool(y))) => x.cmp(y),
+        (Some(Value::Number(x)), Some(Value::Number(y))) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(x)), Some(Value::String(y))) => x.cmp(y),
+        (Some(x), Some(y)) if rank(a) == rank(b) => x.to_string().cmp(&y.to_string()),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn headers_of(value: &Value) -> Vec<String> {
+    match value {
+        Value::Object(map) => map.keys().cloned().collect(),
+        _ => vec!["value".to_string()],
+    }
+}
+
+/// Flattens a value into string cells, rendering nested objects and arrays as compact json.
+fn row_cells(value: &Value, headers: &[String]) -> Vec<String> {
+    match value {
+        Value::Object(map) => headers
+            .iter()
+            .map(|h| map.get(h).map(cell_text).unwrap_or_default())
+            .collect(),
+        other => vec![cell_text(other)],
+    }
+}
+
+fn to_rows(values: &[Value]) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut headers: Vec<String> = vec![];
+    for v in values {
+        for h in headers_of(v) {
+            if !headers.contains(&h) {
+                headers.push(h);
+            }
+        }
+    }
+    let rows = values.iter().map(|v| row_cells(v, &headers)).collect();
+    (headers, rows)
+}
+
+fn cell_text(v: &Value) -> String {
+    match v {
+        Value::Null => "".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn terminal_width() -> Option<usize> {
+    if let Some(cols) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+    {
+        return Some(cols);
+    }
+    terminal_size::terminal_size().map(|(w, _)| w.0 as usize)
+}
+
+/// Shrinks the widest columns until the table fits, never below `TABLE_MIN_COLUMN_WIDTH`.
+fn fit_widths(natural: &[usize], max_width: Option<usize>) -> Vec<usize> {
+    let mut widths = natural.to_vec();
+    let Some(max_width) = max_width else {
+        return widths;
+    };
+    let gaps = TABLE_COLUMN_GAP * widths.len().saturating_sub(1);
+    while widths.iter().sum::<usize>() + gaps > max_width {
+        let (idx, widest) = match widths.iter().enumerate().max_by_key(|(_, w)| **w) {
+            Some((i, w)) => (i, *w),
+            None => break,
+        };
+        if widest <= TABLE_MIN_COLUMN_WIDTH {
+            break;
+        }
+        widths[idx] = widest - 1;
+    }
+    widths
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    let single_line = text.replace(['\r', '\n'], " ");
+    if single_line.chars().count() <= width {
+        return single_line;
+    }
+    let mut truncated: String = single_line.chars().take(width.saturating_sub(1)).collect();
+    truncated.push(TRUNCATION_MARKER);
+    truncated
+}
+
+fn write_table<W: Write>(
+    headers: &[String],
+    rows: &[Vec<String>],
+    max_width: Option<usize>,
+    out: &mut W,
+) -> Result<(), AppError> {
+    let natural: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(idx, h)| {
+            rows.iter()
+                .map(|r| r[idx].chars().count())
+                .chain(std::iter::once(h.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let widths = fit_widths(&natural, max_width);
+
+    let upper: Vec<String> = headers.iter().map(|h| h.to_uppercase()).collect();
+    write_table_row(&upper, &widths, out)?;
+    for row in rows {
+        write_table_row(row, &widths, out)?;
+    }
+    Ok(())
+}
+
+fn write_table_row<W: Write>(
+    cells: &[String],
+    widths: &[usize],
+    out: &mut W,
+) -> Result<(), AppError> {
+    let line = cells
+        .iter()
+        .zip(widths)
+        .map(|(c, w)| format!("{:<width$}", truncate(c, *w), width = *w))
+        .collect::<Vec<String>>()
+        .join(&" ".repeat(TABLE_COLUMN_GAP));
+    writeln!(out, "{}", line.trim_end())?;
+    Ok(())
+}
+
+fn markdown_escape(cell: &str) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn write_markdown_header<W: Write>(headers: &[String], out: &mut W) -> Result<(), AppError> {
+    write_markdown_row(headers, out)?;
+    writeln!(
+        out,
+        "|{}|",
+        headers.iter().map(|_| " --- ").collect::<Vec<&str>>().join("|")
+    )?;
+    Ok(())
+}
+
+fn write_markdown_row<W: Write>(cells: &[String], out: &mut W) -> Result<(), AppError> {
+    writeln!(
+        out,
+        "| {} |",
+        cells
+            .iter()
+            .map(String::as_str)
+            .map(markdown_escape)
+            .collect::<Vec<String>>()
+            .join(" | ")
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Item {
+        id: String,
+        name: Option<String>,
+        released: bool,
+    }
+
+    fn items() -> Vec<Item> {
+        vec![
+            Item {
+                id: "1".to_string(),
+                name: Some("first | release".to_string()),
+                released: true,
+            },
+            Item {
+                id: "20".to_string(),
+                name: None,
+                released: false,
+            },
+        ]
+    }
+
+    fn render(format: Format) -> String {
+        render_with(OutputOptions {
+            format,
+            columns: None,
+            sort_by: None,
+            descending: false,
+        })
+    }
+
+    fn render_with(options: OutputOptions) -> String {
+        let mut out: Vec<u8> = vec![];
+        write_stream(items().into_iter().map(Ok), &options, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn ndjson_writes_one_document_per_line() {
+        insta::assert_snapshot!(render(Format::Ndjson), @r###"
+        {"id":"1","name":"first | release","released":true}
+        {"id":"20","name":null,"released":false}
+        "###);
+    }
+
+    #[test]
+    fn markdown_escapes_pipes() {
+        insta::assert_snapshot!(render(Format::Markdown), @r###"
+        | id | name | released |
+        | --- | --- | --- |
+        | 1 | first \| release | true |
+        | 20 |  | false |
+        "###);
+    }
+
+    #[test]
+    fn yaml_lists_items() {
+        insta::assert_snapshot!(render(Format::Yaml), @r###"
+        - id: '1'
+          name: first | release
+          released: true
+        - id: '20'
+          name: null
+          released: false
+        "###);
+    }
+
+    #[test]
+    fn table_truncates_to_width() {
+        let values: Vec<Value> = items().iter().map(|i| to_value(i).unwrap()).collect();
+        let (headers, rows) = to_rows(&values);
+        let mut out: Vec<u8> = vec![];
+        write_table(&headers, &rows, Some(24), &mut out).unwrap();
+        insta::assert_snapshot!(String::from_utf8(out).unwrap(), @r###"
+        ID  NAME        RELEASED
+        1   first | r…  true
+        20              false
+        "###);
+    }
+
+    #[test]
+    fn csv_keeps_field_order() {
+        insta::assert_snapshot!(render(Format::Csv), @r###"
+        id,name,released
+        1,first | release,true
+        20,,false
+        "###);
+    }
+
+    #[test]
+    fn columns_and_sorting() {
+        let options = OutputOptions {
+            format: Format::Csv,
+            columns: Some(vec!["released".to_string(), "id".to_string()]),
+            sort_by: Some("released".to_string()),
+            descending: false,
+        };
+        insta::assert_snapshot!(render_with(options), @r###"
+        released,id
+        false,20
+        true,1
+        "###);
+    }
+
+    #[test]
+    fn dotted_paths_select_nested_values() {
+        let issue = serde_json::json!({
+            "key": "FOO-1",
+            "fields": {"status": {"name": "Done"}, "fixVersions": [{"name": "1.0"}]}
+        });
+        assert_eq!(
+            value_at_path(&issue, "fields.status.name"),
+            Some(&Value::from("Done"))
+        );
+        assert_eq!(
+            value_at_path(&issue, "fields.fixVersions.0.name"),
+            Some(&Value::from("1.0"))
+        );
+        assert_eq!(value_at_path(&issue, "fields.missing"), None);
+    }
+
+    #[test]
+    fn descending_numeric_sort() {
+        let mut sorted = vec![
+            serde_json::json!({"n": 2}),
+            serde_json::json!({"n": 10}),
+            serde_json::json!({"n": null}),
+        ];
+        sort(&mut sorted, "n", true);
+        assert_eq!(
+            sorted,
+            vec![
+                serde_json::json!({"n": 10}),
+                serde_json::json!({"n": 2}),
+                serde_json::json!({"n": null}),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_streams_a_single_array() {
+        insta::assert_snapshot!(render(Format::Json), @r###"[{"id":"1","name":"first | release","released":true},{"id":"20","name":null,"released":false}]"###);
+    }
+
+    #[test]
+    fn stream_stops_at_first_error() {
+        let items: Vec<Result<Item, AppError>> = vec![
+            Ok(items().remove(0)),
+            Err(AppError::DeserializationError),
+        ];
+        let options = OutputOptions {
+            format: Format::Ndjson,
+            columns: None,
+            sort_by: None,
+            descending: false,
+        };
+        let mut out: Vec<u8> = vec![];
+        let res = write_stream(items, &options, &mut out);
+        assert!(matches!(res, Err(AppError::DeserializationError)));
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"id\":\"1\",\"name\":\"first | release\",\"released\":true}\n"
+        );
+    }
+}
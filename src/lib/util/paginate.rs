@@ -0,0 +1,282 @@
+use super::super::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub const MAX_ITEMS_HELP: &str = "stop after this many items, even if more pages remain";
+
+/// A single page returned by a paged jira endpoint.
+/// Jira reports the rest of the listing in one of three ways: `startAt/maxResults/total/isLast`,
+/// search style `startAt/maxResults/total`, or a `nextPageToken`; implement whichever applies.
+pub trait Page<T> {
+    fn into_values(self) -> Vec<T>;
+    fn total(&self) -> Option<u64> {
+        None
+    }
+    fn is_last(&self) -> Option<bool> {
+        None
+    }
+    fn next_page_token(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The `startAt/maxResults/total/isLast` page most jira listings return, which jira calls a
+/// page bean.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PageBean<T> {
+    pub(crate) total: u64,
+    pub(crate) start_at: u64,
+    pub(crate) is_last: bool,
+    pub(crate) values: Vec<T>,
+}
+
+impl<T> Page<T> for PageBean<T> {
+    fn into_values(self) -> Vec<T> {
+        self.values
+    }
+    fn total(&self) -> Option<u64> {
+        Some(self.total)
+    }
+    fn is_last(&self) -> Option<bool> {
+        Some(self.is_last)
+    }
+}
+
+/// Paging parameters for the next request. Offset based endpoints use `start_at`,
+/// token based endpoints use `next_page_token`.
+#[derive(Debug, Clone, Default)]
+pub struct PageRequest {
+    pub start_at: u64,
+    pub max_results: u64,
+    pub next_page_token: Option<String>,
+}
+
+/// Yields items as pages arrive, only requesting the next page once the current one is used up.
+pub struct Paginator<T, P, F>
+where
+    P: Page<T>,
+    F: FnMut(&PageRequest) -> Result<P, AppError>,
+{
+    fetch: F,
+    request: PageRequest,
+    unpaginate: bool,
+    max_items: Option<u64>,
+    yielded: u64,
+    buffered: VecDeque<T>,
+    done: bool,
+    /// set once a page has carried a `nextPageToken`; from then on a page without one is the last
+    token_paged: bool,
+}
+
+impl<T, P, F> Paginator<T, P, F>
+where
+    P: Page<T>,
+    F: FnMut(&PageRequest) -> Result<P, AppError>,
+{
+    /// When `unpaginate` is false only the first page is fetched.
+    pub fn new(start_at: u64, page_size: u64, unpaginate: bool, fetch: F) -> Self {
+        Paginator {
+            fetch,
+            request: PageRequest {
+                start_at,
+                max_results: page_size,
+                next_page_token: None,
+            },
+            unpaginate,
+            max_items: None,
+            yielded: 0,
+            buffered: VecDeque::new(),
+            done: false,
+            token_paged: false,
+        }
+    }
+
+    /// Stop after this many items, even if more pages remain.
+    pub fn max_items(mut self, max_items: Option<u64>) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    fn fetch_page(&mut self) -> Result<(), AppError> {
+        if let Some(max) = self.max_items {
+            let remaining = max - self.yielded;
+            self.request.max_results = self.request.max_results.min(remaining).max(1);
+        }
+        let page = (self.fetch)(&self.request)?;
+        let total = page.total();
+        let is_last = page.is_last();
+        let token = page.next_page_token();
+        let values = page.into_values();
+        let received = values.len() as u64;
+        self.request.start_at += received;
+
+        self.done = !self.unpaginate
+            || received == 0
+            || match (&token, is_last, total) {
+                (Some(_), Some(true), _) => true,
+                (Some(_), _, _) => false,
+                (None, _, _) if self.token_paged => true,
+                (None, Some(last), _) => last,
+                (None, None, Some(total)) => self.request.start_at >= total,
+                (None, None, None) => received < self.request.max_results,
+            };
+        self.token_paged |= token.is_some();
+        self.request.next_page_token = token;
+        self.buffered.extend(values);
+        Ok(())
+    }
+}
+
+impl<T, P, F> Iterator for Paginator<T, P, F>
+where
+    P: Page<T>,
+    F: FnMut(&PageRequest) -> Result<P, AppError>,
+{
+    type Item = Result<T, AppError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.max_items.is_some_and(|max| self.yielded >= max) {
+            return None;
+        }
+        if self.buffered.is_empty() && !self.done {
+            if let Err(e) = self.fetch_page() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        let item = self.buffered.pop_front()?;
+        self.yielded += 1;
+        Some(Ok(item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A page from an endpoint that pages with `nextPageToken`.
+    struct TokenPaginated<T> {
+        next_page_token: Option<String>,
+        is_last: Option<bool>,
+        values: Vec<T>,
+    }
+
+    impl<T> Page<T> for TokenPaginated<T> {
+        fn into_values(self) -> Vec<T> {
+            self.values
+        }
+        fn is_last(&self) -> Option<bool> {
+            self.is_last
+        }
+        fn next_page_token(&self) -> Option<String> {
+            self.next_page_token.clone()
+        }
+    }
+
+    struct OffsetPage {
+        values: Vec<u64>,
+        total: u64,
+    }
+
+    impl Page<u64> for OffsetPage {
+        fn into_values(self) -> Vec<u64> {
+            self.values
+        }
+        fn total(&self) -> Option<u64> {
+            Some(self.total)
+        }
+    }
+
+    fn offset_source(total: u64) -> impl FnMut(&PageRequest) -> Result<OffsetPage, AppError> {
+        move |req: &PageRequest| {
+            Ok(OffsetPage {
+                values: (req.start_at..(req.start_at + req.max_results).min(total)).collect(),
+                total,
+            })
+        }
+    }
+
+    #[test]
+    fn offset_paging_follows_total() {
+        let items: Vec<u64> = Paginator::new(0, 2, true, offset_source(5))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn single_page_unless_unpaginated() {
+        let items: Vec<u64> = Paginator::new(2, 2, false, offset_source(5))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, vec![2, 3]);
+    }
+
+    #[test]
+    fn max_items_stops_early() {
+        let mut requests: Vec<u64> = vec![];
+        let mut source = offset_source(100);
+        let items: Vec<u64> = Paginator::new(0, 3, true, |req: &PageRequest| {
+            requests.push(req.max_results);
+            source(req)
+        })
+        .max_items(Some(7))
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!(items, (0..7).collect::<Vec<u64>>());
+        assert_eq!(requests, vec![3, 3, 1]);
+    }
+
+    #[test]
+    fn token_paging_follows_next_page_token() {
+        let items: Vec<u64> = Paginator::new(0, 2, true, |req: &PageRequest| {
+            let (values, next_page_token) = match req.next_page_token.as_deref() {
+                None => (vec![1, 2], Some("b".to_string())),
+                Some("b") => (vec![3, 4], Some("c".to_string())),
+                _ => (vec![5], None),
+            };
+            Ok(TokenPaginated {
+                next_page_token,
+                is_last: None,
+                values,
+            })
+        })
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn token_paging_ends_at_a_full_page_without_a_token() {
+        let mut requests = 0;
+        let items: Vec<u64> = Paginator::new(0, 2, true, |req: &PageRequest| {
+            requests += 1;
+            let (values, next_page_token) = match req.next_page_token.as_deref() {
+                None => (vec![1, 2], Some("b".to_string())),
+                _ => (vec![3, 4], None),
+            };
+            Ok(TokenPaginated {
+                next_page_token,
+                is_last: None,
+                values,
+            })
+        })
+        .take(10)
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!(items, vec![1, 2, 3, 4]);
+        assert_eq!(requests, 2);
+    }
+
+    #[test]
+    fn errors_end_the_iteration() {
+        let results: Vec<Result<u64, AppError>> =
+            Paginator::new(0, 2, true, |_: &PageRequest| -> Result<OffsetPage, AppError> {
+                Err(AppError::DeserializationError)
+            })
+            .collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(special_module_name)]
+
 extern crate core;
 
 mod lib;
@@ -18,6 +20,16 @@ pub struct Cli {
     #[clap(value_enum)]
     #[arg(long, default_value_t = Format::Csv, help = "how returned items should be formatted")]
     output_format: Format,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "comma separated fields to output, in order; use dots for nested fields ex fields.status.name"
+    )]
+    columns: Option<Vec<String>>,
+    #[arg(long, help = "field to sort output by; use dots for nested fields")]
+    sort_by: Option<String>,
+    #[arg(long, default_value_t = false, requires = "sort_by", help = "sort in descending order")]
+    desc: bool,
     #[arg(long, short, help = "email address the auth token belongs to")]
     user_email: String,
     #[arg(
@@ -30,16 +42,62 @@ pub struct Cli {
     command: Option<Commands>,
 }
 
+#[cfg(test)]
+impl Cli {
+    pub(crate) fn for_tests(base_jira_url: String) -> Cli {
+        Cli {
+            auth_token: "".to_string(),
+            output_format: Format::Csv,
+            columns: None,
+            sort_by: None,
+            desc: false,
+            user_email: "".to_string(),
+            base_jira_url: base_jira_url.trim_end_matches('/').to_string(),
+            command: None,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// list and optionally filter releases
     ListReleases(releases::ListReleasesArgs),
     /// create a new release
     CreateRelease(releases::CreateReleaseArgs),
+    /// print the name the next semantic version release would get
+    NextReleaseName(releases::NextReleaseNameArgs),
     /// delete a release and optionally update tickets to point to a different one
     DeleteRelease(releases::DeleteReleaseArgs),
     /// update a release
     UpdateRelease(releases::UpdateReleaseArgs),
+    /// fold one release into another, or every duplicate matching a pattern
+    MergeReleases(releases::MergeReleasesArgs),
+    /// archive or delete old released versions
+    PruneReleases(releases::PruneReleasesArgs),
+    /// change where a release sits in the project's order, or sort them all
+    MoveRelease(releases::MoveReleaseArgs),
+    /// create or update the same release in several projects
+    SyncRelease(releases::SyncReleaseArgs),
+    /// list projects, optionally by category or type
+    ListProjects(projects::ListProjectsArgs),
+    /// show a project's details and issue types
+    GetProject(projects::GetProjectArgs),
+    /// list the issue types a project uses
+    ListIssueTypes(projects::ProjectMetadataArgs),
+    /// list the statuses a project's workflows use
+    ListStatuses(projects::ProjectMetadataArgs),
+    /// list the priorities a project allows
+    ListPriorities(projects::ProjectMetadataArgs),
+    /// list the fields issues can have, with their ids and types
+    ListFields(fields::ListFieldsArgs),
+    /// list and optionally filter a project's components
+    ListComponents(components::ListComponentsArgs),
+    /// create a component
+    CreateComponent(components::CreateComponentArgs),
+    /// update a component
+    UpdateComponent(components::UpdateComponentArgs),
+    /// delete a component and optionally move its tickets to a different one
+    DeleteComponent(components::DeleteComponentArgs),
     /// list possible transitions for specified issue
     ListIssueTransitions(issues::ListIssueTransitionsArgs),
     /// transition issue
@@ -48,8 +106,28 @@ enum Commands {
     UpdateIssue(issues::UpdateIssueArgs),
     /// jql search for issues
     SearchIssues(issues::SearchIssuesArgs),
+    /// assign an issue to someone, yourself, or no one
+    AssignIssue(users::AssignIssueArgs),
+    /// look up users by email address or display name
+    FindUser(users::FindUserArgs),
+    /// show the user the given credentials belong to
+    Whoami(users::WhoamiArgs),
     /// release completed issues
     ReleaseAllCompletedIssues(compositions::ReleaseCompletedIssuesArgs),
+    /// release the issues referenced by commits between two git refs
+    ReleaseFromGit(compositions::ReleaseFromGitArgs),
+    /// work out a release-all-completed-issues run and save it for review
+    PlanRelease(compositions::PlanReleaseArgs),
+    /// run a saved release plan if jira still matches it
+    ApplyReleasePlan(compositions::ApplyReleasePlanArgs),
+    /// list the issues only in one of two releases, or in both
+    CompareReleases(compositions::CompareReleasesArgs),
+    /// report whether a release is ready to cut, exiting non-zero if not
+    ReleaseStatus(compositions::ReleaseStatusArgs),
+    /// check the base url, credentials and permissions, printing what passed and what failed
+    Doctor(doctor::DoctorArgs),
+    /// run the steps described in a yaml pipeline file
+    Run(pipelines::RunPipelineArgs),
 }
 
 fn main() -> Result<(), AppError> {
@@ -62,21 +140,54 @@ fn main() -> Result<(), AppError> {
             releases::execute_list_releases(&cli, release_args)
         }
         Some(Commands::CreateRelease(args)) => releases::execute_create_release(&cli, args),
+        Some(Commands::NextReleaseName(args)) => releases::execute_next_release_name(&cli, args),
         Some(Commands::DeleteRelease(args)) => releases::execute_delete_release(&cli, args),
         Some(Commands::UpdateRelease(args)) => releases::execute_update_release(&cli, args),
+        Some(Commands::MergeReleases(args)) => releases::execute_merge_releases(&cli, args),
+        Some(Commands::PruneReleases(args)) => releases::execute_prune_releases(&cli, args),
+        Some(Commands::MoveRelease(args)) => releases::execute_move_release(&cli, args),
+        Some(Commands::SyncRelease(args)) => releases::execute_sync_release(&cli, args),
+        Some(Commands::ListProjects(args)) => projects::execute_list_projects(&cli, args),
+        Some(Commands::GetProject(args)) => projects::execute_get_project(&cli, args),
+        Some(Commands::ListIssueTypes(args)) => projects::execute_list_issue_types(&cli, args),
+        Some(Commands::ListStatuses(args)) => projects::execute_list_statuses(&cli, args),
+        Some(Commands::ListPriorities(args)) => projects::execute_list_priorities(&cli, args),
+        Some(Commands::ListFields(args)) => fields::execute_list_fields(&cli, args),
+        Some(Commands::ListComponents(args)) => components::execute_list_components(&cli, args),
+        Some(Commands::CreateComponent(args)) => components::execute_create_component(&cli, args),
+        Some(Commands::UpdateComponent(args)) => components::execute_update_component(&cli, args),
+        Some(Commands::DeleteComponent(args)) => components::execute_delete_component(&cli, args),
         Some(Commands::ListIssueTransitions(args)) => issues::execute_list_transitions(&cli, args),
         Some(Commands::TransitionIssue(args)) => issues::execute_transition_issue(&cli, args),
         Some(Commands::UpdateIssue(args)) => issues::execute_update_issue(&cli, args),
         Some(Commands::SearchIssues(args)) => issues::execute_search_issues(&cli, args),
+        Some(Commands::AssignIssue(args)) => users::execute_assign_issue(&cli, args),
+        Some(Commands::FindUser(args)) => users::execute_find_user(&cli, args),
+        Some(Commands::Whoami(args)) => users::execute_whoami(&cli, args),
         Some(Commands::ReleaseAllCompletedIssues(args)) => {
             compositions::execute_do_release(&cli, args)
         }
+        Some(Commands::ReleaseFromGit(args)) => compositions::execute_release_from_git(&cli, args),
+        Some(Commands::PlanRelease(args)) => compositions::execute_plan_release(&cli, args),
+        Some(Commands::ApplyReleasePlan(args)) => {
+            compositions::execute_apply_release_plan(&cli, args)
+        }
+        Some(Commands::CompareReleases(args)) => {
+            compositions::execute_compare_releases(&cli, args)
+        }
+        Some(Commands::ReleaseStatus(args)) => compositions::execute_release_status(&cli, args),
+        Some(Commands::Doctor(args)) => doctor::execute_doctor(&cli, args),
+        Some(Commands::Run(args)) => pipelines::execute_run_pipeline(&cli, args),
 
         None => Ok(()),
     };
 
     match result {
         Ok(_) => result,
+        Err(AppError::ReleaseNotReady(reasons, exit_code)) => {
+            error!("release is not ready: {}", reasons);
+            std::process::exit(exit_code)
+        }
         Err(e) => {
             error!("{:?}", e);
             Err(e)
@@ -84,3 +195,14 @@ fn main() -> Result<(), AppError> {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_is_well_formed() {
+        Cli::command().debug_assert();
+    }
+}